@@ -0,0 +1,394 @@
+use crate::{canonical, capture, CopySide, FatCopyError, FatIOError, FatRenameError};
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// # Run blocking io on the tokio blocking pool with a fat error
+/// The closure gets the path and runs via `tokio::task::spawn_blocking`.
+/// Both its io error and a failure of the task itself (a panic or cancellation, turned into an io error of kind `Other`) are returned as [`FatIOError`] for `path`,
+/// so a single `?` handles both.
+/// # Examples
+/// ```rust
+/// use std::io::Read;
+/// use vg_errortools::fat_spawn_blocking;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let error = fat_spawn_blocking("does/not/exist.txt", |path| {
+///     let mut content = String::new();
+///     std::fs::File::open(path)?.read_to_string(&mut content)?;
+///     Ok(content)
+/// })
+/// .await
+/// .unwrap_err();
+/// assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+/// # }
+/// ```
+pub async fn fat_spawn_blocking<T, F>(path: impl AsRef<Path>, f: F) -> Result<T, FatIOError>
+where
+    T: Send + 'static,
+    F: FnOnce(&Path) -> std::io::Result<T> + Send + 'static,
+{
+    let task_path: PathBuf = path.as_ref().to_path_buf();
+    let path = canonical::recorded_path(path.as_ref());
+    let result = match tokio::task::spawn_blocking(move || f(&task_path)).await {
+        Ok(result) => result,
+        Err(join_error) => Err(std::io::Error::other(join_error)),
+    };
+    result.map_err(|e| FatIOError::from_shared_path(e, path))
+}
+
+/// # A failed tokio task with the name it was spawned with
+/// Wraps the `tokio::task::JoinError` of a panicked or cancelled task, so it surfaces as "task 'mesh-export' failed: …" in error chains.
+/// Create it with [`fat_spawn`] or [`fat_join`].
+#[derive(Debug)]
+pub struct FatTaskError {
+    name: String,
+    source: tokio::task::JoinError,
+}
+
+impl FatTaskError {
+    /// the name of the task
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// true if the task panicked
+    pub fn is_panic(&self) -> bool {
+        self.source.is_panic()
+    }
+
+    /// true if the task was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.source.is_cancelled()
+    }
+
+    /// the wrapped join error
+    pub fn into_join_error(self) -> tokio::task::JoinError {
+        self.source
+    }
+}
+
+impl Display for FatTaskError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "task '{}' failed: {}", self.name, self.source)
+    }
+}
+
+impl Error for FatTaskError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// # Await a tokio task, naming it in the error if it failed
+/// # Examples
+/// ```rust
+/// use vg_errortools::fat_join;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let handle = tokio::spawn(async { panic!("no vertices") });
+/// let error = fat_join("mesh-export", handle).await.unwrap_err();
+/// assert!(error.is_panic());
+/// assert!(error.to_string().starts_with("task 'mesh-export' failed: "));
+/// # }
+/// ```
+pub async fn fat_join<T>(
+    name: impl Into<String>,
+    handle: tokio::task::JoinHandle<T>,
+) -> Result<T, FatTaskError> {
+    handle.await.map_err(|source| FatTaskError {
+        name: name.into(),
+        source,
+    })
+}
+
+/// # Spawn a named tokio task
+/// Same as `tokio::spawn`, the returned future yields a [`FatTaskError`] carrying `name` if the task panicked or got cancelled.
+/// The task is spawned immediately, awaiting the returned future only collects its result.
+pub fn fat_spawn<F>(
+    name: impl Into<String>,
+    future: F,
+) -> impl Future<Output = Result<F::Output, FatTaskError>>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    fat_join(name, tokio::spawn(future))
+}
+
+/// # The entries of a directory as a stream with fat errors
+/// Created by [`fat_read_dir`], failures while reading entries carry the path of the directory.
+#[derive(Debug)]
+pub struct FatReadDir {
+    inner: tokio::fs::ReadDir,
+    path: Arc<Path>,
+}
+
+impl FatReadDir {
+    /// the path of the directory
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// the next entry, `None` at the end of the directory
+    pub async fn next_entry(&mut self) -> Result<Option<tokio::fs::DirEntry>, FatIOError> {
+        self.inner
+            .next_entry()
+            .await
+            .map_err(|e| FatIOError::from_shared_path(e, self.path.clone()))
+    }
+}
+
+impl futures_core::Stream for FatReadDir {
+    type Item = Result<tokio::fs::DirEntry, FatIOError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_entry(cx).map(|entry| match entry {
+            Ok(entry) => entry.map(Ok),
+            Err(e) => Some(Err(FatIOError::from_shared_path(e, self.path.clone()))),
+        })
+    }
+}
+
+/// # Read a directory with `tokio::fs::read_dir`, with fat errors for opening and for each entry
+/// # Examples
+/// ```rust
+/// use futures_util::StreamExt;
+/// use vg_errortools::fat_read_dir;
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let entries = fat_read_dir("src").await.unwrap();
+/// let names: Vec<_> = entries.map(|entry| entry.unwrap().file_name()).collect().await;
+/// assert!(names.iter().any(|name| name == "lib.rs"));
+/// let error = fat_read_dir("does/not/exist").await.unwrap_err();
+/// assert_eq!(error.path(), std::path::Path::new("does/not/exist"));
+/// # }
+/// ```
+pub async fn fat_read_dir(path: impl AsRef<Path>) -> Result<FatReadDir, FatIOError> {
+    let inner = tokio::fs::read_dir(path.as_ref()).await;
+    let path = canonical::recorded_path(path.as_ref());
+    match inner {
+        Ok(inner) => Ok(FatReadDir { inner, path }),
+        Err(e) => Err(FatIOError::from_shared_path(e, path)),
+    }
+}
+
+/// # Write `contents` to a file with a fat error, wraps `tokio::fs::write`
+/// # Examples
+/// ```rust
+/// use vg_errortools::fat_tokio_write;
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let error = fat_tokio_write("does/not/exist/mesh.stl", b"solid").await.unwrap_err();
+/// assert_eq!(error.path(), std::path::Path::new("does/not/exist/mesh.stl"));
+/// assert_eq!(error.operation(), Some("tokio::fs::write"));
+/// # }
+/// ```
+pub async fn fat_tokio_write(
+    path: impl AsRef<Path>,
+    contents: impl AsRef<[u8]>,
+) -> Result<(), FatIOError> {
+    let path = path.as_ref();
+    let started = capture::start_timer();
+    tokio::fs::write(path, contents).await.map_err(|e| {
+        FatIOError::from_shared_path(e, canonical::recorded_path(path))
+            .with_operation("tokio::fs::write")
+            .with_elapsed(started)
+    })
+}
+
+/// # Copy a file with an error naming both paths, wraps `tokio::fs::copy`
+/// This keeps the fast paths of the platform copy, which does not tell the failing side, so the side of the [`FatCopyError`] is [`CopySide::Unknown`].
+/// Use [`crate::fat_copy_with_progress`], e.g. in `spawn_blocking`, to learn the side and the offset. Returns the number of bytes copied.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{fat_tokio_copy, CopySide};
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let error = fat_tokio_copy("Cargo.toml", "does/not/exist/Cargo.toml").await.unwrap_err();
+/// assert_eq!(error.side(), CopySide::Unknown);
+/// assert_eq!(error.to_path(), std::path::Path::new("does/not/exist/Cargo.toml"));
+/// assert!(error.to_string().starts_with("copying 'Cargo.toml' to 'does/not/exist/Cargo.toml' failed: "));
+/// # }
+/// ```
+pub async fn fat_tokio_copy(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+) -> Result<u64, FatCopyError> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    tokio::fs::copy(from, to).await.map_err(|e| {
+        FatCopyError::new(
+            canonical::recorded_path(from).to_path_buf(),
+            canonical::recorded_path(to).to_path_buf(),
+            CopySide::Unknown,
+            0,
+            e,
+        )
+    })
+}
+
+/// # Rename a file with an error naming both paths, wraps `tokio::fs::rename`
+/// # Examples
+/// ```rust
+/// use vg_errortools::fat_tokio_rename;
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let error = fat_tokio_rename("does/not/exist.raw", "scan.raw").await.unwrap_err();
+/// assert_eq!(error.from_path(), std::path::Path::new("does/not/exist.raw"));
+/// assert!(error.to_string().starts_with("renaming 'does/not/exist.raw' to 'scan.raw' failed with error "));
+/// # }
+/// ```
+pub async fn fat_tokio_rename(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+) -> Result<(), FatRenameError> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    tokio::fs::rename(from, to).await.map_err(|e| {
+        FatRenameError::new(
+            canonical::recorded_path(from).to_path_buf(),
+            canonical::recorded_path(to).to_path_buf(),
+            e,
+        )
+    })
+}
+
+pin_project_lite::pin_project! {
+    /// # An `AsyncRead` adaptor embedding a path into every error
+    /// Errors of the inner reader are returned as io errors of the same kind carrying a [`FatIOError`] for the path,
+    /// so the path survives generic codec layers and can be recovered with [`FatIOError::from_io_error_payload`].
+    /// # Examples
+    /// ```rust
+    /// use tokio::io::AsyncReadExt;
+    /// use vg_errortools::{FatAsyncReader, FatIOError};
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let mut reader = FatAsyncReader::new(&b"voxels"[..], "volume_17.raw");
+    /// let mut text = String::new();
+    /// reader.read_to_string(&mut text).await.unwrap();
+    /// assert_eq!(text, "voxels");
+    /// let path = std::env::temp_dir().join("vg_errortools_async_reader_doc.raw");
+    /// let write_only = tokio::fs::File::create(&path).await.unwrap();
+    /// let mut reader = FatAsyncReader::new(write_only, &path);
+    /// let error = reader.read_to_string(&mut text).await.unwrap_err();
+    /// let fat = FatIOError::from_io_error_payload(&error).unwrap();
+    /// assert_eq!(fat.path(), path);
+    /// # }
+    /// ```
+    #[derive(Debug)]
+    pub struct FatAsyncReader<R> {
+        #[pin]
+        inner: R,
+        path: Arc<Path>,
+    }
+}
+
+impl<R> FatAsyncReader<R> {
+    /// wrap `inner`, embedding `path` into its errors
+    pub fn new(inner: R, path: impl AsRef<Path>) -> Self {
+        FatAsyncReader {
+            inner,
+            path: canonical::recorded_path(path.as_ref()),
+        }
+    }
+
+    /// the embedded path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// unwrap the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: tokio::io::AsyncRead> tokio::io::AsyncRead for FatAsyncReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.project();
+        this.inner
+            .poll_read(cx, buf)
+            .map_err(|e| FatIOError::embed(e, this.path))
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// # An `AsyncWrite` adaptor embedding a path into every error
+    /// The writing counterpart of [`FatAsyncReader`].
+    #[derive(Debug)]
+    pub struct FatAsyncWriter<W> {
+        #[pin]
+        inner: W,
+        path: Arc<Path>,
+    }
+}
+
+impl<W> FatAsyncWriter<W> {
+    /// wrap `inner`, embedding `path` into its errors
+    pub fn new(inner: W, path: impl AsRef<Path>) -> Self {
+        FatAsyncWriter {
+            inner,
+            path: canonical::recorded_path(path.as_ref()),
+        }
+    }
+
+    /// the embedded path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// unwrap the inner writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: tokio::io::AsyncWrite> tokio::io::AsyncWrite for FatAsyncWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.project();
+        this.inner
+            .poll_write(cx, buf)
+            .map_err(|e| FatIOError::embed(e, this.path))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.project();
+        this.inner
+            .poll_flush(cx)
+            .map_err(|e| FatIOError::embed(e, this.path))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.project();
+        this.inner
+            .poll_shutdown(cx)
+            .map_err(|e| FatIOError::embed(e, this.path))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.project();
+        this.inner
+            .poll_write_vectored(cx, bufs)
+            .map_err(|e| FatIOError::embed(e, this.path))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{ContextError, FatIOError};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// # A context which can be attached to errors of type `E`
+/// Paths turn io errors into [`FatIOError`]s, static labels wrap any error into a [`ContextError`].
+pub trait ErrorContext<E> {
+    /// the error with the context attached
+    type Error;
+
+    /// attach the context to `error`
+    fn attach(&self, error: E) -> Self::Error;
+}
+
+impl ErrorContext<std::io::Error> for Arc<Path> {
+    type Error = FatIOError;
+
+    fn attach(&self, error: std::io::Error) -> FatIOError {
+        FatIOError::from_shared_path(error, self.clone())
+    }
+}
+
+impl ErrorContext<std::io::Error> for PathBuf {
+    type Error = FatIOError;
+
+    fn attach(&self, error: std::io::Error) -> FatIOError {
+        FatIOError::from_std_io_err(error, self.clone())
+    }
+}
+
+impl ErrorContext<std::io::Error> for &Path {
+    type Error = FatIOError;
+
+    fn attach(&self, error: std::io::Error) -> FatIOError {
+        FatIOError::from_std_io_err(error, self.to_path_buf())
+    }
+}
+
+impl<E> ErrorContext<E> for &'static str {
+    type Error = ContextError<E>;
+
+    fn attach(&self, error: E) -> ContextError<E> {
+        ContextError::new(self, error)
+    }
+}
+
+/// # Call an std::io function and name it in the error
+/// Expands to [`crate::fat_io_wrap_std`] and attaches the stringified function path with [`crate::FatIOError::with_operation`],
+/// so the message says which operation was attempted.
+/// # Examples
+/// ```rust
+/// use vg_errortools::fat_call;
+/// let path = std::path::PathBuf::from("does/not/exist.txt");
+/// let error = fat_call!(std::fs::remove_file, &path).unwrap_err();
+/// assert_eq!(error.operation(), Some("std::fs::remove_file"));
+/// assert!(error.to_string().starts_with("remove_file on 'does/not/exist.txt' failed with error "));
+/// ```
+#[macro_export]
+macro_rules! fat_call {
+    ($function:path, $path:expr $(,)?) => {
+        $crate::fat_io_wrap_std($path, &$function)
+            .map_err(|e| e.with_operation(stringify!($function)))
+    };
+}
+
+/// # Call an std::io function capturing as much as possible for the error
+/// Like [`fat_call!`], and additionally stores the text of the path expression and the source location of the call,
+/// see [`crate::FatIOError::path_expression`] and [`crate::FatIOError::location`]. Reports of a `MainError` show both from [`crate::Verbosity::Detailed`] on.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{fat_io, MainError, MainErrorOptions, Verbosity};
+/// let settings = std::path::PathBuf::from("does/not/exist.json");
+/// let (error, line) = (fat_io!(std::fs::read_to_string, &settings).unwrap_err(), line!());
+/// assert_eq!(error.operation(), Some("std::fs::read_to_string"));
+/// assert_eq!(error.path_expression(), Some("&settings"));
+/// assert_eq!(error.location().unwrap().line(), line);
+/// let report = MainError::from(error).render(&MainErrorOptions::new().verbosity(Verbosity::Detailed));
+/// assert!(report.ends_with("\npath expression: &settings"));
+/// ```
+#[macro_export]
+macro_rules! fat_io {
+    ($function:path, $path:expr $(,)?) => {
+        $crate::fat_io_wrap_std($path, &$function).map_err(|e| {
+            e.with_operation(stringify!($function))
+                .with_call_site(stringify!($path), std::panic::Location::caller())
+        })
+    };
+}
+
+/// # Attach a context to every error escaping a block
+/// The block runs in a closure, so `?` leaves the block, not the enclosing function. Its errors are converted into `std::io::Error`
+/// or the error type given as second argument, and the context is attached with [`ErrorContext`]:
+/// paths (`&Path`, `PathBuf`, `Arc<Path>`) turn io errors into [`FatIOError`]s, static labels wrap any error into a [`ContextError`].
+/// String literals are labels, pass paths as `Path` or `PathBuf`.
+/// # Examples
+/// ```rust
+/// use std::io::Read;
+/// use std::path::Path;
+/// use vg_errortools::fat_try;
+/// let path = Path::new("does/not/exist.txt");
+/// let error = fat_try!(path, {
+///     let mut content = String::new();
+///     std::fs::File::open(path)?.read_to_string(&mut content)?;
+///     content
+/// })
+/// .unwrap_err();
+/// assert_eq!(error.path(), path);
+///
+/// let error = fat_try!("parsing the header", std::num::ParseIntError, {
+///     let width: u32 = "64".parse()?;
+///     let height: u32 = "x".parse()?;
+///     width * height
+/// })
+/// .unwrap_err();
+/// assert_eq!(error.label(), "parsing the header");
+/// ```
+#[macro_export]
+macro_rules! fat_try {
+    ($context:expr, $body:block $(,)?) => {
+        $crate::fat_try!($context, std::io::Error, $body)
+    };
+    ($context:expr, $error:ty, $body:block $(,)?) => {
+        (|| -> ::core::result::Result<_, $error> { ::core::result::Result::Ok($body) })()
+            .map_err(|e| $crate::ErrorContext::attach(&$context, e))
+    };
+}
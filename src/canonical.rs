@@ -0,0 +1,50 @@
+use crate::{capture, capture_options, FatIOError};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// the canonical form of `path`, for missing files the canonical parent joined with the file name, the absolute path if that fails too
+fn canonical_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    match (parent.canonicalize(), path.file_name()) {
+        (Ok(parent), Some(name)) => parent.join(name),
+        _ => std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()),
+    }
+}
+
+/// the path stored in errors of the wrappers, canonical if enabled by [`crate::CaptureOptions::canonical_path`]
+pub(crate) fn recorded_path(path: &Path) -> Arc<Path> {
+    if capture_options().captures_canonical_path() {
+        Arc::from(canonical_path(path))
+    } else {
+        Arc::from(path)
+    }
+}
+
+/// # Wrapper for std::io functions recording the canonical path
+/// Same as [`crate::fat_io_wrap_std`], but the error holds the absolute, canonicalized path, while `f` is still called with the original argument.
+/// Relative paths in errors are ambiguous when the tool changes directories. For missing files the parent directory is canonicalized.
+/// To canonicalize in all wrappers, enable [`crate::CaptureOptions::canonical_path`].
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+/// use vg_errortools::fat_io_wrap_std_canonical;
+/// let error = fat_io_wrap_std_canonical("does_not_exist.txt", &File::open).unwrap_err();
+/// assert!(error.path().is_absolute());
+/// assert_eq!(error.path(), std::env::current_dir().unwrap().canonicalize().unwrap().join("does_not_exist.txt"));
+/// ```
+pub fn fat_io_wrap_std_canonical<'a, T, P: AsRef<Path> + ?Sized>(
+    path: &'a P,
+    f: &dyn Fn(&'a P) -> std::io::Result<T>,
+) -> Result<T, FatIOError> {
+    let started = capture::start_timer();
+    f(path).map_err(|e| {
+        FatIOError::from_shared_path(e, Arc::from(canonical_path(path.as_ref())))
+            .with_elapsed(started)
+    })
+}
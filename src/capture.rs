@@ -0,0 +1,386 @@
+use crate::limit::HumanSize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::thread::ThreadId;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+const TIMESTAMP: u8 = 1;
+const THREAD: u8 = 1 << 1;
+const CANONICAL_PATH: u8 = 1 << 2;
+const DURATION: u8 = 1 << 3;
+const ENVIRONMENT: u8 = 1 << 4;
+const METADATA: u8 = 1 << 5;
+
+static GLOBAL_CAPTURE: AtomicU8 = AtomicU8::new(0);
+
+/// # Opt-in information captured when an error is created
+/// Capturing is disabled by default, select what to capture once at startup with [`set_capture_options`].
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_capture_options, CaptureOptions, MainError};
+/// set_capture_options(CaptureOptions::new().timestamp(true));
+/// let error = MainError::from(std::io::Error::other("disk on fire"));
+/// assert!(error.timestamp().is_some());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CaptureOptions {
+    flags: u8,
+}
+
+impl CaptureOptions {
+    /// capture nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn flag(mut self, flag: u8, enabled: bool) -> Self {
+        if enabled {
+            self.flags |= flag;
+        } else {
+            self.flags &= !flag;
+        }
+        self
+    }
+
+    fn has(self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+
+    /// capture the time a [`crate::MainError`] is created
+    pub fn timestamp(self, enabled: bool) -> Self {
+        self.flag(TIMESTAMP, enabled)
+    }
+
+    /// true if the creation time is captured
+    pub fn captures_timestamp(self) -> bool {
+        self.has(TIMESTAMP)
+    }
+
+    /// capture the thread a [`crate::FatIOError`] or [`crate::MainError`] is created on
+    pub fn thread(self, enabled: bool) -> Self {
+        self.flag(THREAD, enabled)
+    }
+
+    /// true if the creating thread is captured
+    pub fn captures_thread(self) -> bool {
+        self.has(THREAD)
+    }
+
+    /// # Record the canonical path in errors of all wrappers, like [`crate::fat_io_wrap_std_canonical`]
+    /// This covers the `fat_io_wrap_*` wrappers, the `fat_*` functions taking a path and the sync and async readers and writers.
+    /// The [`crate::FatIOError`] constructors and [`crate::ErrorContext`] keep the path as given.
+    pub fn canonical_path(self, enabled: bool) -> Self {
+        self.flag(CANONICAL_PATH, enabled)
+    }
+
+    /// true if the wrappers record the canonical path
+    pub fn captures_canonical_path(self) -> bool {
+        self.has(CANONICAL_PATH)
+    }
+
+    /// measure how long the failed operation of the wrappers ran, see [`crate::FatIOError::duration`]
+    pub fn duration(self, enabled: bool) -> Self {
+        self.flag(DURATION, enabled)
+    }
+
+    /// true if the wrappers measure the duration of the operation
+    pub fn captures_duration(self) -> bool {
+        self.has(DURATION)
+    }
+
+    /// capture the working directory, user and host a [`crate::MainError`] is created in, see [`Environment`]
+    pub fn environment(self, enabled: bool) -> Self {
+        self.flag(ENVIRONMENT, enabled)
+    }
+
+    /// true if the environment is captured
+    pub fn captures_environment(self) -> bool {
+        self.has(ENVIRONMENT)
+    }
+
+    /// snapshot the metadata of the file a [`crate::FatIOError`] is created for, see [`FileMetadata`]
+    pub fn metadata(self, enabled: bool) -> Self {
+        self.flag(METADATA, enabled)
+    }
+
+    /// true if the file metadata is captured
+    pub fn captures_metadata(self) -> bool {
+        self.has(METADATA)
+    }
+}
+
+/// # The thread an error was created on
+/// Captured if enabled by [`CaptureOptions::thread`] and shown in reports from [`crate::Verbosity::Detailed`] on.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_capture_options, CaptureOptions, FatIOError, MainError, MainErrorOptions, Verbosity};
+/// set_capture_options(CaptureOptions::new().thread(true));
+/// let worker = std::thread::Builder::new().name("worker-3".to_string());
+/// let fat = worker
+///     .spawn(|| FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "batch_17.raw".into()))
+///     .unwrap()
+///     .join()
+///     .unwrap();
+/// assert_eq!(fat.thread().unwrap().name(), Some("worker-3"));
+/// let error = MainError::from(fat);
+/// let verbose = MainErrorOptions::new().verbosity(Verbosity::Detailed);
+/// assert!(error.render(&verbose).contains("\nthread: 'worker-3'"));
+/// assert!(!error.render(&MainErrorOptions::new()).contains("thread"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThreadInfo {
+    name: Option<String>,
+    id: ThreadId,
+}
+
+impl ThreadInfo {
+    /// the current thread
+    pub fn current() -> Self {
+        let thread = std::thread::current();
+        ThreadInfo {
+            name: thread.name().map(str::to_string),
+            id: thread.id(),
+        }
+    }
+
+    /// the name of the thread, if it has one
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// the id of the thread
+    pub fn id(&self) -> ThreadId {
+        self.id
+    }
+
+    /// capture the current thread if enabled by the process wide [`CaptureOptions`]
+    pub(crate) fn capture() -> Option<Self> {
+        capture_options()
+            .captures_thread()
+            .then(ThreadInfo::current)
+    }
+}
+
+impl fmt::Display for ThreadInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "'{name}' ({:?})", self.id),
+            None => write!(f, "{:?}", self.id),
+        }
+    }
+}
+
+/// # The working directory, user and host an error was created in
+/// Captured if enabled by [`CaptureOptions::environment`] and shown in the `context` line of reports, to answer the first questions of every support session.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_capture_options, CaptureOptions, MainError, MainErrorOptions};
+/// set_capture_options(CaptureOptions::new().environment(true));
+/// let error = MainError::from(std::io::Error::other("disk on fire"));
+/// let environment = error.environment().unwrap();
+/// assert_eq!(environment.cwd(), Some(std::env::current_dir().unwrap().as_path()));
+/// assert!(error.render(&MainErrorOptions::new()).contains("\ncontext: cwd '"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Environment {
+    cwd: Option<PathBuf>,
+    user: Option<String>,
+    host: Option<String>,
+}
+
+impl Environment {
+    /// the environment of the current process
+    pub fn current() -> Self {
+        Environment {
+            cwd: std::env::current_dir().ok(),
+            user: ["USER", "LOGNAME", "USERNAME"]
+                .into_iter()
+                .find_map(non_empty_var),
+            host: host_name(),
+        }
+    }
+
+    /// the working directory
+    pub fn cwd(&self) -> Option<&Path> {
+        self.cwd.as_deref()
+    }
+
+    /// the name of the user
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    /// the name of the host
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// capture the current environment if enabled by the process wide [`CaptureOptions`]
+    pub(crate) fn capture() -> Option<Self> {
+        capture_options()
+            .captures_environment()
+            .then(Environment::current)
+    }
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn host_name() -> Option<String> {
+    ["HOSTNAME", "COMPUTERNAME"]
+        .into_iter()
+        .find_map(non_empty_var)
+        .or_else(|| {
+            ["/proc/sys/kernel/hostname", "/etc/hostname"]
+                .into_iter()
+                .filter_map(|file| std::fs::read_to_string(file).ok())
+                .map(|name| name.trim().to_string())
+                .find(|name| !name.is_empty())
+        })
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unknown = "<unknown>";
+        write!(
+            f,
+            "cwd '{}', user '{}', host '{}'",
+            self.cwd
+                .as_deref()
+                .map_or(unknown.into(), Path::to_string_lossy),
+            self.user.as_deref().unwrap_or(unknown),
+            self.host.as_deref().unwrap_or(unknown)
+        )
+    }
+}
+
+/// # The metadata of a file at the time an error was created for it
+/// Captured if enabled by [`CaptureOptions::metadata`] and the file exists, and shown in the `file` line of reports from [`crate::Verbosity::Detailed`] on.
+/// It answers "it worked yesterday" questions: was the file empty, changed recently or read-only?
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_capture_options, CaptureOptions, FatIOError, MainError, MainErrorOptions, Verbosity};
+/// set_capture_options(CaptureOptions::new().metadata(true));
+/// let file = std::env::temp_dir().join("vg_errortools_metadata_doc.raw");
+/// std::fs::write(&file, [0u8; 2048]).unwrap();
+/// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::InvalidData.into(), file.clone());
+/// let metadata = fat.metadata().unwrap();
+/// assert_eq!(metadata.size(), 2048);
+/// assert!(!metadata.is_readonly());
+/// let verbose = MainErrorOptions::new().verbosity(Verbosity::Detailed);
+/// assert!(MainError::from(fat).render(&verbose).contains("\nfile: 2 KiB, modified "));
+/// # std::fs::remove_file(file).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileMetadata {
+    size: u64,
+    modified: Option<SystemTime>,
+    readonly: bool,
+    mode: Option<u32>,
+}
+
+impl FileMetadata {
+    /// the metadata of `path`, following symlinks, `None` if it does not exist or is inaccessible
+    pub fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        #[cfg(unix)]
+        let mode = Some(std::os::unix::fs::PermissionsExt::mode(
+            &metadata.permissions(),
+        ));
+        #[cfg(not(unix))]
+        let mode = None;
+        Some(FileMetadata {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            readonly: metadata.permissions().readonly(),
+            mode,
+        })
+    }
+
+    /// the size in bytes
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// the last modification time, if the platform records it
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    /// true if the file is read-only
+    pub fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// the unix permission bits, `None` on other platforms
+    pub fn mode(&self) -> Option<u32> {
+        self.mode
+    }
+
+    /// capture the metadata of `path` if enabled by the process wide [`CaptureOptions`]
+    pub(crate) fn capture(path: &Path) -> Option<Self> {
+        if !capture_options().captures_metadata() {
+            return None;
+        }
+        FileMetadata::of(path)
+    }
+}
+
+impl fmt::Display for FileMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", HumanSize(self.size))?;
+        if let Some(modified) = self.modified {
+            write!(f, ", modified {}", format_timestamp(modified))?;
+        }
+        if self.readonly {
+            f.write_str(", read-only")?;
+        }
+        if let Some(mode) = self.mode {
+            write!(f, ", mode {:o}", mode & 0o7777)?;
+        }
+        Ok(())
+    }
+}
+
+/// the start of an operation if its duration is captured, see [`CaptureOptions::duration`]
+pub(crate) fn start_timer() -> Option<Instant> {
+    capture_options().captures_duration().then(Instant::now)
+}
+
+/// select the process wide [`CaptureOptions`]
+pub fn set_capture_options(options: CaptureOptions) {
+    GLOBAL_CAPTURE.store(options.flags, Ordering::Relaxed);
+}
+
+/// the process wide [`CaptureOptions`]
+pub fn capture_options() -> CaptureOptions {
+    CaptureOptions {
+        flags: GLOBAL_CAPTURE.load(Ordering::Relaxed),
+    }
+}
+
+/// format as RFC 3339 timestamp in UTC with millisecond precision, e.g. `2022-06-30T08:15:42.123Z`
+pub(crate) fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3_600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
@@ -0,0 +1,93 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::mpsc;
+
+/// Marker for the error types of channels, see [`ChannelResultExt`]
+pub trait ChannelError: Error + 'static {}
+
+impl<T: 'static> ChannelError for mpsc::SendError<T> {}
+impl<T: 'static> ChannelError for mpsc::TrySendError<T> {}
+impl ChannelError for mpsc::RecvError {}
+impl ChannelError for mpsc::TryRecvError {}
+impl ChannelError for mpsc::RecvTimeoutError {}
+
+#[cfg(feature = "tokio")]
+mod tokio_channels {
+    use super::ChannelError;
+    use tokio::sync::{broadcast, mpsc, oneshot, watch};
+
+    impl<T: 'static> ChannelError for mpsc::error::SendError<T> {}
+    impl<T: 'static> ChannelError for mpsc::error::TrySendError<T> {}
+    impl ChannelError for mpsc::error::TryRecvError {}
+    impl ChannelError for oneshot::error::RecvError {}
+    impl ChannelError for oneshot::error::TryRecvError {}
+    impl<T: std::fmt::Debug + 'static> ChannelError for broadcast::error::SendError<T> {}
+    impl ChannelError for broadcast::error::RecvError {}
+    impl ChannelError for broadcast::error::TryRecvError {}
+    impl<T: 'static> ChannelError for watch::error::SendError<T> {}
+    impl ChannelError for watch::error::RecvError {}
+}
+
+/// # A channel error with the label of the channel
+/// Several channels failing in one chain are otherwise indistinguishable, this shows up as "channel 'results' failed: sending on a closed channel".
+/// Create it with [`ChannelResultExt::channel`]. A value which could not be sent stays available in the wrapped error.
+pub struct FatChannelError<E> {
+    label: &'static str,
+    source: E,
+}
+
+impl<E> FatChannelError<E> {
+    /// the label of the channel
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    /// the wrapped channel error, e.g. to recover an unsent value
+    pub fn into_inner(self) -> E {
+        self.source
+    }
+}
+
+impl<E: Debug> Debug for FatChannelError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FatChannelError")
+            .field("label", &self.label)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl<E: Display> Display for FatChannelError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "channel '{}' failed: {}", self.label, self.source)
+    }
+}
+
+impl<E: ChannelError> Error for FatChannelError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// # Label channel errors
+/// # Examples
+/// ```rust
+/// use std::sync::mpsc;
+/// use vg_errortools::ChannelResultExt;
+/// let (results, receiver) = mpsc::channel::<u32>();
+/// drop(receiver);
+/// let error = results.send(17).channel("results").unwrap_err();
+/// assert_eq!(error.to_string(), "channel 'results' failed: sending on a closed channel");
+/// assert_eq!(error.into_inner().0, 17);
+/// ```
+pub trait ChannelResultExt<T, E> {
+    /// wrap the error in a [`FatChannelError`] with `label`
+    fn channel(self, label: &'static str) -> Result<T, FatChannelError<E>>;
+}
+
+impl<T, E: ChannelError> ChannelResultExt<T, E> for Result<T, E> {
+    fn channel(self, label: &'static str) -> Result<T, FatChannelError<E>> {
+        self.map_err(|source| FatChannelError { label, source })
+    }
+}
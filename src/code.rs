@@ -0,0 +1,164 @@
+use crate::report::Detail;
+use crate::{chain, FatIOError, Verbosity};
+use std::any::TypeId;
+use std::error::Error;
+use std::io::ErrorKind;
+use std::sync::{LazyLock, RwLock};
+
+/// # A stable identifier of an error, independent of its English message
+/// Codes like `VG-IO-0004` let support and localization teams refer to an error even if the wording changes.
+/// Register the type with [`register_error_code`], reports of [`crate::MainError`] then show the code of the first registered error in the chain.
+/// [`FatIOError`] is registered from the start and implements it with one code per io error kind:
+///
+/// | kind | code |
+/// |------|------|
+/// | `NotFound` | `VG-IO-0001` |
+/// | `PermissionDenied` | `VG-IO-0002` |
+/// | `AlreadyExists` | `VG-IO-0003` |
+/// | `InvalidInput` | `VG-IO-0004` |
+/// | `InvalidData` | `VG-IO-0005` |
+/// | `TimedOut` | `VG-IO-0006` |
+/// | `UnexpectedEof` | `VG-IO-0007` |
+/// | `Interrupted` | `VG-IO-0008` |
+/// | `Unsupported` | `VG-IO-0009` |
+/// | `OutOfMemory` | `VG-IO-0010` |
+/// | everything else | `VG-IO-0000` |
+/// # Examples
+/// ```rust
+/// use vg_errortools::{register_error_code, ErrorCode, MainError};
+/// #[derive(Debug, thiserror::Error)]
+/// #[error("calibration table is empty")]
+/// struct EmptyCalibration;
+/// impl ErrorCode for EmptyCalibration {
+///     fn error_code(&self) -> &'static str {
+///         "VG-CAL-0001"
+///     }
+///     fn documentation_url(&self) -> Option<String> {
+///         Some("https://docs.example.com/errors/VG-CAL-0001".to_string())
+///     }
+/// }
+/// register_error_code::<EmptyCalibration>();
+/// let error = MainError::from(EmptyCalibration);
+/// assert_eq!(
+///     format!("{error:?}"),
+///     "calibration table is empty\ncode: VG-CAL-0001\ndocs: https://docs.example.com/errors/VG-CAL-0001"
+/// );
+/// ```
+pub trait ErrorCode {
+    /// the stable code
+    fn error_code(&self) -> &'static str;
+
+    /// a link to the documentation of the code, none by default, which falls back to [`set_documentation_url_template`]
+    fn documentation_url(&self) -> Option<String> {
+        None
+    }
+}
+
+impl ErrorCode for FatIOError {
+    fn error_code(&self) -> &'static str {
+        match self.kind() {
+            ErrorKind::NotFound => "VG-IO-0001",
+            ErrorKind::PermissionDenied => "VG-IO-0002",
+            ErrorKind::AlreadyExists => "VG-IO-0003",
+            ErrorKind::InvalidInput => "VG-IO-0004",
+            ErrorKind::InvalidData => "VG-IO-0005",
+            ErrorKind::TimedOut => "VG-IO-0006",
+            ErrorKind::UnexpectedEof => "VG-IO-0007",
+            ErrorKind::Interrupted => "VG-IO-0008",
+            ErrorKind::Unsupported => "VG-IO-0009",
+            ErrorKind::OutOfMemory => "VG-IO-0010",
+            _ => "VG-IO-0000",
+        }
+    }
+}
+
+type Lookup = for<'a> fn(&'a (dyn Error + 'static)) -> Option<&'a dyn ErrorCode>;
+
+/// the registered types, the built-in ones included
+static LOOKUPS: LazyLock<RwLock<Vec<(TypeId, Lookup)>>> =
+    LazyLock::new(|| RwLock::new(vec![(TypeId::of::<FatIOError>(), lookup::<FatIOError>)]));
+
+fn lookup<'a, T: ErrorCode + Error + 'static>(
+    error: &'a (dyn Error + 'static),
+) -> Option<&'a dyn ErrorCode> {
+    error.downcast_ref::<T>().map(|e| e as &dyn ErrorCode)
+}
+
+/// # Show the [`ErrorCode`] of `T` in reports
+/// Registering a type twice, or [`FatIOError`] at all, has no further effect.
+pub fn register_error_code<T: ErrorCode + Error + 'static>() {
+    let mut lookups = LOOKUPS.write().unwrap_or_else(|e| e.into_inner());
+    if !lookups.iter().any(|&(id, _)| id == TypeId::of::<T>()) {
+        lookups.push((TypeId::of::<T>(), lookup::<T>));
+    }
+}
+
+static URL_TEMPLATE: RwLock<Option<String>> = RwLock::new(None);
+
+/// # Link every [`ErrorCode`] without its own [`ErrorCode::documentation_url`] to a remediation article
+/// `{code}` in `template` is replaced by the code, e.g. `https://docs.example.com/errors/{code}`, and reports show the link in their `docs:` line.
+/// `None` removes the links again. Call this once at startup.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_documentation_url_template, FatIOError, MainError};
+/// set_documentation_url_template(Some("https://docs.example.com/errors/{code}".to_string()));
+/// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "scan.raw".into());
+/// let error = MainError::from(fat);
+/// assert!(format!("{error:?}").ends_with("\ncode: VG-IO-0001\ndocs: https://docs.example.com/errors/VG-IO-0001"));
+/// ```
+pub fn set_documentation_url_template(template: Option<String>) {
+    *URL_TEMPLATE.write().unwrap_or_else(|e| e.into_inner()) = template;
+}
+
+/// the template set by [`set_documentation_url_template`]
+pub fn documentation_url_template() -> Option<String> {
+    URL_TEMPLATE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// the code and documentation URL of the first error in the chain whose type is registered with [`register_error_code`]
+/// The lookups are copied out first, so user implementations may register codes or report errors themselves.
+fn find(error: &(dyn Error + 'static)) -> Option<(&'static str, Option<String>)> {
+    let lookups = LOOKUPS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    chain(error).find_map(|e| {
+        lookups
+            .iter()
+            .find_map(|(_, lookup)| lookup(e))
+            .map(|code| {
+                let url = code.documentation_url().or_else(|| {
+                    documentation_url_template()
+                        .map(|template| template.replace("{code}", code.error_code()))
+                });
+                (code.error_code(), url)
+            })
+    })
+}
+
+/// the code of the first error in the chain whose type is registered with [`register_error_code`]
+pub fn error_code_of(error: &(dyn Error + 'static)) -> Option<&'static str> {
+    find(error).map(|(code, _)| code)
+}
+
+/// the report details for the code and documentation URL of the chain, if any
+pub(crate) fn details(error: &(dyn Error + 'static)) -> Vec<Detail> {
+    let Some((code, url)) = find(error) else {
+        return Vec::new();
+    };
+    let mut details = vec![Detail {
+        key: "code",
+        label: "code",
+        value: code.to_string(),
+        verbosity: Verbosity::Normal,
+    }];
+    if let Some(url) = url {
+        details.push(Detail {
+            key: "documentation_url",
+            label: "docs",
+            value: url,
+            verbosity: Verbosity::Normal,
+        });
+    }
+    details
+}
@@ -0,0 +1,101 @@
+use crate::canonical;
+use crate::template::display_path;
+use serde::de::DeserializeOwned;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug)]
+enum ConfigFailure {
+    Read(std::io::Error),
+    Parse {
+        field: Option<String>,
+        source: serde_json::Error,
+    },
+}
+
+/// # A config file which could not be read or deserialized, with its path and the invalid field
+/// Created by [`fat_read_json_config`], the source is the io error of reading or the `serde_json` error.
+#[derive(Debug)]
+pub struct FatConfigError {
+    file: Arc<Path>,
+    failure: ConfigFailure,
+}
+
+impl FatConfigError {
+    /// the path of the config file
+    pub fn path(&self) -> &Path {
+        &self.file
+    }
+
+    /// the dotted path of the invalid field like `server.tls.cert_file`, `None` for read errors and errors at the top level
+    pub fn field(&self) -> Option<&str> {
+        match &self.failure {
+            ConfigFailure::Parse { field, .. } => field.as_deref(),
+            ConfigFailure::Read(_) => None,
+        }
+    }
+}
+
+impl Display for FatConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let file = display_path(&self.file);
+        match &self.failure {
+            ConfigFailure::Read(e) => write!(f, "reading config file '{file}' failed: {e}"),
+            ConfigFailure::Parse {
+                field: Some(field),
+                source,
+            } => write!(f, "config file '{file}' is invalid at '{field}': {source}"),
+            ConfigFailure::Parse {
+                field: None,
+                source,
+            } => write!(f, "config file '{file}' is invalid: {source}"),
+        }
+    }
+}
+
+impl Error for FatConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.failure {
+            ConfigFailure::Read(e) => Some(e),
+            ConfigFailure::Parse { source, .. } => Some(source),
+        }
+    }
+}
+
+/// # Read and deserialize a JSON config file, reporting the path and the invalid field
+/// # Examples
+/// ```rust
+/// use serde::Deserialize;
+/// use vg_errortools::fat_read_json_config;
+/// #[derive(Deserialize)]
+/// struct Tls { cert_file: String }
+/// #[derive(Deserialize)]
+/// struct Server { tls: Tls }
+/// #[derive(Deserialize)]
+/// struct Config { server: Server }
+/// let path = std::env::temp_dir().join("vg_errortools_config_doc.json");
+/// std::fs::write(&path, r#"{"server": {"tls": {"cert_file": 42}}}"#).unwrap();
+/// let error = fat_read_json_config::<Config, _>(&path).err().unwrap();
+/// assert_eq!(error.field(), Some("server.tls.cert_file"));
+/// assert!(error.to_string().contains("is invalid at 'server.tls.cert_file': invalid type"));
+/// ```
+pub fn fat_read_json_config<T: DeserializeOwned, P: AsRef<Path> + ?Sized>(
+    path: &P,
+) -> Result<T, FatConfigError> {
+    let error = |failure| FatConfigError {
+        file: canonical::recorded_path(path.as_ref()),
+        failure,
+    };
+    let bytes = std::fs::read(path).map_err(|e| error(ConfigFailure::Read(e)))?;
+    let deserializer = &mut serde_json::Deserializer::from_slice(&bytes);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let field = e.path().to_string();
+        error(ConfigFailure::Parse {
+            field: (field != ".").then_some(field),
+            source: e.into_inner(),
+        })
+    })
+}
@@ -0,0 +1,47 @@
+use crate::{add_report_sink, install_panic_hook, MainErrorOptions};
+use std::io::Write;
+
+/// # A report sink writing to the browser console
+/// Every report is passed as one `console.error` call, so the message and its causes stay together in the developer tools.
+/// Register it with [`add_report_sink`] or use [`install_console_error_hook`].
+#[derive(Debug, Default)]
+pub struct ConsoleSink {
+    buffer: Vec<u8>,
+}
+
+impl ConsoleSink {
+    /// a sink with nothing written yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Write for ConsoleSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    /// pass everything written since the last flush to `console.error`
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.buffer.is_empty() {
+            let text = String::from_utf8_lossy(&self.buffer);
+            web_sys::console::error_1(&text.trim_end().into());
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+}
+
+/// # Route reports and panics to `console.error`
+/// Registers a [`ConsoleSink`] and installs the panic hook of [`install_panic_hook`] with `options`, so `MainError` reports
+/// and panics of a `wasm32-unknown-unknown` build show up in the browser console with their whole chain.
+/// # Examples
+/// ```rust, no_run
+/// use vg_errortools::{install_console_error_hook, MainErrorOptions, Style};
+/// install_console_error_hook(MainErrorOptions::new().style(Style::Fancy));
+/// ```
+pub fn install_console_error_hook(options: MainErrorOptions) {
+    add_report_sink(Box::new(ConsoleSink::new()));
+    install_panic_hook(options);
+}
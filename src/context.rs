@@ -0,0 +1,105 @@
+use core::error::Error;
+use core::fmt;
+use core::fmt::{Display, Formatter};
+
+/// # An error with a static label describing what failed
+/// The path-less sibling of [`crate::FatIOError`] for code without file system, it allocates nothing and is available without the `std` feature.
+/// The label is the message, the wrapped error is the source, so the chain keeps both.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{display_chain, ContextError};
+/// let error = ContextError::new("reading sensor calibration", std::fmt::Error);
+/// assert_eq!(error.label(), "reading sensor calibration");
+/// assert_eq!(
+///     display_chain(&error).to_string(),
+///     "reading sensor calibration: an error occurred when formatting an argument"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextError<E> {
+    label: &'static str,
+    source: E,
+}
+
+impl<E> ContextError<E> {
+    /// wrap `source` with `label`
+    pub fn new(label: &'static str, source: E) -> Self {
+        ContextError { label, source }
+    }
+
+    /// the label describing what failed
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    /// the wrapped error
+    pub fn inner(&self) -> &E {
+        &self.source
+    }
+
+    /// unwrap the wrapped error
+    pub fn into_inner(self) -> E {
+        self.source
+    }
+}
+
+impl<E> Display for ContextError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label)
+    }
+}
+
+impl<E: Error + 'static> Error for ContextError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// # Label the error of a `Result` with a static string, anyhow-style context without allocation
+/// # Examples
+/// ```rust
+/// use vg_errortools::{display_chain, ContextResultExt};
+/// let error = "17x".parse::<u32>().label("loading mesh cache").unwrap_err();
+/// assert_eq!(error.label(), "loading mesh cache");
+/// assert_eq!(display_chain(&error).to_string(), "loading mesh cache: invalid digit found in string");
+/// ```
+pub trait ContextResultExt<T, E> {
+    /// wrap the error in a [`ContextError`] with `label`
+    fn label(self, label: &'static str) -> Result<T, ContextError<E>>;
+}
+
+impl<T, E> ContextResultExt<T, E> for Result<T, E> {
+    fn label(self, label: &'static str) -> Result<T, ContextError<E>> {
+        self.map_err(|source| ContextError { label, source })
+    }
+}
+
+/// # An error and all of its causes joined by `: ` on a single line
+/// Created by [`display_chain`], it formats without allocating and is available without the `std` feature.
+#[derive(Clone, Copy)]
+pub struct DisplayChain<'a> {
+    error: &'a (dyn Error + 'static),
+}
+
+impl Display for DisplayChain<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, error) in crate::chain(self.error).enumerate() {
+            if i > 0 {
+                f.write_str(": ")?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for DisplayChain<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// display `error` followed by its causes, see [`DisplayChain`]
+pub fn display_chain<'a>(error: &'a (dyn Error + 'static)) -> DisplayChain<'a> {
+    DisplayChain { error }
+}
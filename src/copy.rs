@@ -0,0 +1,181 @@
+use crate::template::display_path;
+use crate::{canonical, FatIOError};
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// The side of a copy which failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CopySide {
+    /// opening or reading the source
+    Source,
+    /// creating or writing the destination
+    Destination,
+    /// not known, the copy only reported that it failed, like `tokio::fs::copy` in `fat_tokio_copy`
+    Unknown,
+}
+
+/// # A failed copy with both paths, the failing side and the byte offset
+/// Created by [`fat_copy_with_progress`] and `fat_tokio_copy`, the source is the io error.
+#[derive(Debug)]
+pub struct FatCopyError {
+    from: PathBuf,
+    to: PathBuf,
+    side: CopySide,
+    offset: u64,
+    source: std::io::Error,
+}
+
+impl FatCopyError {
+    pub(crate) fn new(
+        from: PathBuf,
+        to: PathBuf,
+        side: CopySide,
+        offset: u64,
+        source: std::io::Error,
+    ) -> Self {
+        FatCopyError {
+            from,
+            to,
+            side,
+            offset,
+            source,
+        }
+    }
+
+    /// the path copied from
+    pub fn from_path(&self) -> &Path {
+        &self.from
+    }
+
+    /// the path copied to
+    pub fn to_path(&self) -> &Path {
+        &self.to
+    }
+
+    /// the side which failed
+    pub fn side(&self) -> CopySide {
+        self.side
+    }
+
+    /// the number of bytes copied before the failure, `0` for a [`CopySide::Unknown`] side
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// the kind of the io error
+    pub fn kind(&self) -> ErrorKind {
+        self.source.kind()
+    }
+}
+
+impl Display for FatCopyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let side = match self.side {
+            CopySide::Source => "reading the source",
+            CopySide::Destination => "writing the destination",
+            CopySide::Unknown => {
+                return write!(
+                    f,
+                    "copying '{}' to '{}' failed: {}",
+                    display_path(&self.from),
+                    display_path(&self.to),
+                    self.source
+                )
+            }
+        };
+        write!(
+            f,
+            "copying '{}' to '{}' failed at byte {} while {side}: {}",
+            display_path(&self.from),
+            display_path(&self.to),
+            self.offset,
+            self.source
+        )
+    }
+}
+
+impl Error for FatCopyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// keeps the kind
+impl From<FatCopyError> for std::io::Error {
+    fn from(e: FatCopyError) -> Self {
+        std::io::Error::new(e.kind(), e)
+    }
+}
+
+/// the path of the failing side, the source path for a [`CopySide::Unknown`] side, the offset is lost
+impl From<FatCopyError> for FatIOError {
+    fn from(e: FatCopyError) -> Self {
+        let path = match e.side {
+            CopySide::Source | CopySide::Unknown => e.from,
+            CopySide::Destination => e.to,
+        };
+        FatIOError::from_std_io_err(e.source, path)
+    }
+}
+
+/// # Copy a file, reporting progress and which side failed at which offset
+/// The destination is created or truncated. After every chunk `progress` gets the number of bytes copied so far and the size of the source, if known.
+/// Returns the number of bytes copied.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{fat_copy_with_progress, CopySide};
+/// let from = std::env::temp_dir().join("vg_errortools_copy_doc.raw");
+/// let to = std::env::temp_dir().join("vg_errortools_copy_doc_backup.raw");
+/// std::fs::write(&from, vec![7u8; 100_000]).unwrap();
+/// let mut reported = 0;
+/// let copied = fat_copy_with_progress(&from, &to, |copied, total| {
+///     assert_eq!(total, Some(100_000));
+///     reported = copied;
+/// })
+/// .unwrap();
+/// assert_eq!((copied, reported), (100_000, 100_000));
+/// let error = fat_copy_with_progress(&from, "does/not/exist/backup.raw", |_, _| {}).unwrap_err();
+/// assert_eq!((error.side(), error.offset()), (CopySide::Destination, 0));
+/// ```
+pub fn fat_copy_with_progress(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    mut progress: impl FnMut(u64, Option<u64>),
+) -> Result<u64, FatCopyError> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let error = |side, offset, source| {
+        FatCopyError::new(
+            canonical::recorded_path(from).to_path_buf(),
+            canonical::recorded_path(to).to_path_buf(),
+            side,
+            offset,
+            source,
+        )
+    };
+    let mut reader = File::open(from).map_err(|e| error(CopySide::Source, 0, e))?;
+    let total = reader.metadata().ok().map(|metadata| metadata.len());
+    let mut writer = File::create(to).map_err(|e| error(CopySide::Destination, 0, e))?;
+    let mut buffer = vec![0; 64 * 1024];
+    let mut copied = 0;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(error(CopySide::Source, copied, e)),
+        };
+        writer
+            .write_all(&buffer[..read])
+            .map_err(|e| error(CopySide::Destination, copied, e))?;
+        copied += read as u64;
+        progress(copied, total);
+    }
+    writer
+        .flush()
+        .map_err(|e| error(CopySide::Destination, copied, e))?;
+    Ok(copied)
+}
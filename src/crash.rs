@@ -0,0 +1,92 @@
+use crate::capture::format_timestamp;
+use crate::template::display_path;
+use crate::{fat_io_wrap_std, FatIOError, MainError, MainErrorOptions, Style, Verbosity};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+static CRASH_REPORT_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// # Write crash reports of fatal errors into `dir`
+/// Once set, a [`MainError`] reported by [`crate::run_main`] writes a crash report with [`MainError::write_crash_report`] before the process exits
+/// and mentions the file in its output, so end users can attach it to support requests. `None` disables this again.
+/// Formatting the error with `{:?}` only mentions a crash report that was written already.
+pub fn set_crash_report_dir(dir: Option<PathBuf>) {
+    *CRASH_REPORT_DIR.write().unwrap_or_else(|e| e.into_inner()) = dir;
+}
+
+/// the directory set by [`set_crash_report_dir`]
+pub fn crash_report_dir() -> Option<PathBuf> {
+    CRASH_REPORT_DIR
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+impl<R> MainError<R> {
+    /// # Write a crash report file into `dir`
+    /// The file is named after the current time and process id and contains the full cause chain,
+    /// a snapshot of the environment (program arguments, working directory, OS and architecture) and the backtrace if one was captured.
+    /// `dir` is created if it does not exist. Returns the path of the written file.
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::MainError;
+    /// let error = MainError::from(std::io::Error::other("disk on fire"));
+    /// let dir = std::env::temp_dir().join("vg_errortools_crash_doc");
+    /// let file = error.write_crash_report(&dir).unwrap();
+    /// let content = std::fs::read_to_string(&file).unwrap();
+    /// assert!(content.starts_with("disk on fire\n"));
+    /// assert!(content.contains("\narguments: "));
+    /// # std::fs::remove_dir_all(dir).unwrap();
+    /// ```
+    pub fn write_crash_report(&self, dir: impl AsRef<Path>) -> Result<PathBuf, FatIOError> {
+        let dir = dir.as_ref();
+        fat_io_wrap_std(dir, &std::fs::create_dir_all)?;
+        let now = SystemTime::now();
+        let stamp: String = format_timestamp(now)
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect();
+        let file = dir.join(format!("crash-{stamp}-{}.txt", std::process::id()));
+        let options = MainErrorOptions::new()
+            .style(Style::Plain)
+            .verbosity(Verbosity::Full)
+            .ignore_env();
+        let mut content = self.render(&options);
+        content.push_str("\n\nenvironment:");
+        let args: Vec<String> = std::env::args_os()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let _ = write!(content, "\narguments: {args:?}");
+        if let Ok(cwd) = std::env::current_dir() {
+            let _ = write!(content, "\nworking directory: {}", cwd.display());
+        }
+        let _ = write!(
+            content,
+            "\nos: {} ({})\nreport written: {}\n",
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            format_timestamp(now)
+        );
+        fat_io_wrap_std(&file, &|file| std::fs::write(file, &content))?;
+        Ok(file)
+    }
+
+    /// write the crash report once if a directory is configured, called on the way out of the process
+    pub(crate) fn write_configured_crash_report(&self) {
+        if let Some(dir) = crash_report_dir() {
+            self.inner
+                .crash_report
+                .get_or_init(|| self.write_crash_report(&dir).map_err(|e| e.to_string()));
+        }
+    }
+
+    /// the note for the report output if a crash report was written or attempted
+    pub(crate) fn crash_report_note(&self) -> Option<String> {
+        Some(match self.inner.crash_report.get()? {
+            Ok(file) => format!("a crash report was written to '{}'", display_path(file)),
+            Err(e) => format!("writing the crash report failed: {e}"),
+        })
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{canonical, FatIOError, FileMetadata};
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// # The file found by [`fat_create_new`] in place of the new one
+/// The io error of kind `AlreadyExists` returned by [`fat_create_new`] wraps it, inside a [`FatIOError`] for the file.
+/// Size and modification time tell whether it is a stale output or lock that can be removed safely.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileExists {
+    metadata: Option<FileMetadata>,
+}
+
+impl FileExists {
+    /// the metadata of the existing file, `None` if it vanished or is inaccessible
+    pub fn metadata(&self) -> Option<&FileMetadata> {
+        self.metadata.as_ref()
+    }
+}
+
+impl Display for FileExists {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.metadata {
+            Some(metadata) => write!(f, "file exists already ({metadata})"),
+            None => f.write_str("file exists already"),
+        }
+    }
+}
+
+impl Error for FileExists {}
+
+/// # Create a new file, failing if it exists
+/// Wraps [`File::create_new`], which creates the file atomically, e.g. for lock files or outputs that must not be overwritten.
+/// If the file exists, the error of kind `AlreadyExists` wraps a [`FileExists`] with the size and modification time of the existing file.
+/// # Examples
+/// ```rust
+/// use std::io::ErrorKind;
+/// use vg_errortools::{fat_create_new, FileExists};
+/// let path = std::env::temp_dir().join("vg_errortools_create_new_doc.lock");
+/// # let _ = std::fs::remove_file(&path);
+/// std::fs::write(&path, "4711").unwrap();
+/// let error = fat_create_new(&path).unwrap_err();
+/// assert_eq!(error.kind(), ErrorKind::AlreadyExists);
+/// assert!(error.to_string().contains("failed with error file exists already (4 B, modified "));
+/// let exists = error.io_error().get_ref().unwrap().downcast_ref::<FileExists>().unwrap();
+/// assert_eq!(exists.metadata().unwrap().size(), 4);
+/// std::fs::remove_file(&path).unwrap();
+/// assert!(fat_create_new(&path).is_ok());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn fat_create_new(path: impl AsRef<Path>) -> Result<File, FatIOError> {
+    let path = path.as_ref();
+    File::create_new(path).map_err(|e| {
+        let e = match e.kind() {
+            ErrorKind::AlreadyExists => {
+                let metadata = FileMetadata::of(path);
+                std::io::Error::new(ErrorKind::AlreadyExists, FileExists { metadata })
+            }
+            _ => e,
+        };
+        FatIOError::from_shared_path(e, canonical::recorded_path(path))
+            .with_operation("std::fs::File::create_new")
+    })
+}
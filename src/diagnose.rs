@@ -0,0 +1,287 @@
+use crate::report::Detail;
+use crate::template::display_path;
+use crate::{chain, FatIOError, Verbosity};
+use std::error::Error;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// what kind of filesystem object `path` is, without following a final symlink first
+fn describe(path: &Path) -> &'static str {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return "missing";
+    };
+    let file_type = metadata.file_type();
+    if file_type.is_symlink() {
+        return match std::fs::metadata(path) {
+            Ok(target) if target.is_dir() => "a symbolic link to a directory",
+            Ok(_) => "a symbolic link to a file",
+            Err(_) => "a broken symbolic link",
+        };
+    }
+    if file_type.is_dir() {
+        return "a directory";
+    }
+    if file_type.is_file() {
+        return "a regular file";
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        if file_type.is_fifo() {
+            return "a named pipe";
+        }
+        if file_type.is_socket() {
+            return "a socket";
+        }
+        if file_type.is_block_device() {
+            return "a block device";
+        }
+        if file_type.is_char_device() {
+            return "a character device";
+        }
+    }
+    "a special file"
+}
+
+/// the operation that failed, `the operation` if it was not recorded
+fn operation(error: &FatIOError) -> String {
+    match error.operation() {
+        Some(operation) => format!("`{operation}`"),
+        None => "the operation".to_string(),
+    }
+}
+
+fn is_a_directory(error: &FatIOError) -> String {
+    let path = error.path();
+    format!(
+        "'{}' is {}, but {} expected a file",
+        display_path(path),
+        describe(path),
+        operation(error)
+    )
+}
+
+fn not_a_directory(error: &FatIOError) -> String {
+    let path = error.path();
+    let blocking = path
+        .ancestors()
+        .skip(1)
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .find(|ancestor| ancestor.exists() && !ancestor.is_dir());
+    match blocking {
+        Some(ancestor) => format!(
+            "'{}' is {}, but the path '{}' uses it as a directory",
+            display_path(ancestor),
+            describe(ancestor),
+            display_path(path)
+        ),
+        None => format!(
+            "'{}' is {}, but {} expected a directory",
+            display_path(path),
+            describe(path),
+            operation(error)
+        ),
+    }
+}
+
+fn crosses_devices(error: &FatIOError) -> String {
+    format!(
+        "'{}' and the destination are on different filesystems and a rename cannot move files between them, copy and remove the file instead, e.g. with `fat_rename_or_copy`",
+        display_path(error.path())
+    )
+}
+
+/// the mount point of the filesystem containing `path`, from the longest matching entry of `/proc/self/mounts`
+#[cfg(target_os = "linux")]
+fn mount_point(path: &Path) -> Option<std::path::PathBuf> {
+    let existing = path.ancestors().find(|ancestor| ancestor.exists())?;
+    let path = std::fs::canonicalize(existing).ok()?;
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    mounts
+        .lines()
+        .filter_map(|line| line.split(' ').nth(1))
+        .map(|mount| std::path::PathBuf::from(unescape_mount(mount)))
+        .filter(|mount| path.starts_with(mount))
+        .max_by_key(|mount| mount.as_os_str().len())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn mount_point(_path: &Path) -> Option<std::path::PathBuf> {
+    None
+}
+
+/// decode the octal escapes like `\040` for spaces of `/proc/self/mounts`
+#[cfg(target_os = "linux")]
+fn unescape_mount(mount: &str) -> String {
+    let mut unescaped = String::with_capacity(mount.len());
+    let mut rest = mount;
+    while let Some(start) = rest.find('\\') {
+        unescaped.push_str(&rest[..start]);
+        let escape = rest.get(start + 1..start + 4);
+        match escape.and_then(|digits| u8::from_str_radix(digits, 8).ok()) {
+            Some(byte) => {
+                unescaped.push(char::from(byte));
+                rest = &rest[start + 4..];
+            }
+            None => {
+                unescaped.push('\\');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+fn read_only_filesystem(error: &FatIOError) -> String {
+    let path = display_path(error.path());
+    let mounted = match mount_point(error.path()) {
+        Some(mount) => format!("mounted read-only at '{}'", display_path(&mount)),
+        None => "mounted read-only".to_string(),
+    };
+    format!(
+        "the filesystem containing '{path}' is {mounted}, this is not a permission problem of the file, remount it writable or write somewhere else"
+    )
+}
+
+/// the soft and hard limit of open files of the process
+#[cfg(unix)]
+fn open_file_limit() -> Option<(String, String)> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: getrlimit only writes the limit into the passed struct
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 {
+        return None;
+    }
+    let show = |value| match value {
+        libc::RLIM_INFINITY => "unlimited".to_string(),
+        value => value.to_string(),
+    };
+    Some((show(limit.rlim_cur), show(limit.rlim_max)))
+}
+
+/// true for `EMFILE`, the per process limit, false for `ENFILE`, the system wide limit, `None` for other errors
+fn too_many_open_files(error: &FatIOError) -> Option<bool> {
+    let code = error.io_error().raw_os_error()?;
+    #[cfg(unix)]
+    return match code {
+        libc::EMFILE => Some(true),
+        libc::ENFILE => Some(false),
+        _ => None,
+    };
+    #[cfg(windows)]
+    return (code == 4).then_some(true);
+    #[cfg(not(any(unix, windows)))]
+    return None;
+}
+
+fn open_files(error: &FatIOError, per_process: bool) -> String {
+    let advice = "or open fewer files at once, e.g. by loading with less concurrency";
+    if !per_process {
+        return format!("the system wide limit of open files is reached, close other programs, raise the limit {advice}");
+    }
+    #[cfg(unix)]
+    if let Some((soft, hard)) = open_file_limit() {
+        return format!(
+            "the process reached its limit of {soft} open files (hard limit {hard}) while opening '{}', raise it with `ulimit -n` {advice}",
+            display_path(error.path())
+        );
+    }
+    format!(
+        "the process reached its limit of open files while opening '{}', raise the limit {advice}",
+        display_path(error.path())
+    )
+}
+
+/// the length limit of paths on Windows without long path support
+const MAX_PATH: usize = 260;
+
+/// the length of `path` in UTF-16 units if it exceeds [`MAX_PATH`] and lacks the `\\?\` prefix lifting the limit
+fn exceeds_max_path(path: &Path) -> Option<usize> {
+    let text = path.to_string_lossy();
+    let length = text.encode_utf16().count();
+    (length >= MAX_PATH && !text.starts_with(r"\\?\")).then_some(length)
+}
+
+fn long_path(error: &FatIOError, length: usize) -> String {
+    format!(
+        r"the path '{}' is {length} characters long, more than the {MAX_PATH} of MAX_PATH, enable long paths with the LongPathsEnabled registry setting and the longPathAware application manifest entry, or prefix the absolute path with \\?\",
+        display_path(error.path())
+    )
+}
+
+impl FatIOError {
+    /// # Explain the failure in plain words, if the crate knows more than the OS message
+    /// The filesystem is inspected when this is called, reports show the explanation in a `hint:` line.
+    /// For `IsADirectory` and `NotADirectory` it states what the path actually is and what the operation expected,
+    /// for `CrossesDevices` (`EXDEV`) that a rename between filesystems needs [`crate::fat_rename_or_copy`]
+    /// for `ReadOnlyFilesystem` (`EROFS`) that the filesystem is mounted read-only, with the mount point on Linux,
+    /// for too many open files (`EMFILE`, `ENFILE`) the current limit and how to stay below it,
+    /// and on Windows for paths longer than `MAX_PATH` how to enable long path support.
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::{fat_io_wrap_std, FatIOError, MainError};
+    /// let dir = std::env::temp_dir();
+    /// let fat = fat_io_wrap_std(&dir, &std::fs::read_to_string).unwrap_err();
+    /// assert_eq!(
+    ///     fat.diagnosis().unwrap(),
+    ///     format!("'{}' is a directory, but the operation expected a file", dir.display())
+    /// );
+    /// let manifest = std::path::Path::new("Cargo.toml/fragment.toml");
+    /// let fat = fat_io_wrap_std(manifest, &std::fs::read_to_string).unwrap_err();
+    /// assert_eq!(
+    ///     fat.diagnosis().unwrap(),
+    ///     "'Cargo.toml' is a regular file, but the path 'Cargo.toml/fragment.toml' uses it as a directory"
+    /// );
+    /// assert!(format!("{:?}", MainError::from(fat)).ends_with("\nhint: 'Cargo.toml' is a regular file, but the path 'Cargo.toml/fragment.toml' uses it as a directory"));
+    /// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::CrossesDevices.into(), "/tmp/scan.raw".into());
+    /// assert!(fat.diagnosis().unwrap().starts_with("'/tmp/scan.raw' and the destination are on different filesystems"));
+    /// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::ReadOnlyFilesystem.into(), "/tmp/scan.raw".into());
+    /// assert!(fat.diagnosis().unwrap().starts_with("the filesystem containing '/tmp/scan.raw' is mounted read-only"));
+    /// # #[cfg(windows)] {
+    /// let long = format!(r"C:\scans\{}.raw", "x".repeat(300));
+    /// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), long.into());
+    /// assert!(fat.diagnosis().unwrap().contains("characters long, more than the 260 of MAX_PATH"));
+    /// # }
+    /// # #[cfg(target_os = "linux")] {
+    /// let fat = FatIOError::from_std_io_err(std::io::Error::from_raw_os_error(24), "/tmp/scan.raw".into());
+    /// assert!(fat.diagnosis().unwrap().starts_with("the process reached its limit of "));
+    /// # }
+    /// ```
+    pub fn diagnosis(&self) -> Option<String> {
+        if cfg!(windows) {
+            if let Some(length) = exceeds_max_path(self.path()) {
+                return Some(long_path(self, length));
+            }
+        }
+        if let Some(per_process) = too_many_open_files(self) {
+            return Some(open_files(self, per_process));
+        }
+        match self.kind() {
+            ErrorKind::IsADirectory => Some(is_a_directory(self)),
+            ErrorKind::NotADirectory => Some(not_a_directory(self)),
+            ErrorKind::CrossesDevices => Some(crosses_devices(self)),
+            ErrorKind::ReadOnlyFilesystem => Some(read_only_filesystem(self)),
+            _ => None,
+        }
+    }
+}
+
+/// the diagnosis of the first [`FatIOError`] in the chain which has one
+pub fn diagnose(error: &(dyn Error + 'static)) -> Option<String> {
+    chain(error)
+        .filter_map(|e| e.downcast_ref::<FatIOError>())
+        .find_map(FatIOError::diagnosis)
+}
+
+/// the report detail of the diagnosis of the chain, if any
+pub(crate) fn details(error: &(dyn Error + 'static)) -> Option<Detail> {
+    diagnose(error).map(|hint| Detail {
+        key: "hint",
+        label: "hint",
+        value: hint,
+        verbosity: Verbosity::Normal,
+    })
+}
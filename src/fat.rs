@@ -0,0 +1,74 @@
+use core::error::Error;
+use core::fmt;
+use core::fmt::{Display, Formatter};
+
+/// # An error together with the context it failed on
+/// The generalization of [`crate::FatIOError`] for contexts which are not paths, like a URL newtype, a database key or a compound struct.
+/// It formats like `FatIOError`, quotes and control characters in the context escaped the same way, and keeps the wrapped error as source.
+/// It is available without the `std` feature, then it allocates nothing and writes the context unescaped.
+/// # Examples
+/// ```rust
+/// use std::fmt;
+/// use vg_errortools::FatError;
+/// struct Key<'a>(&'a str, u32);
+/// impl fmt::Display for Key<'_> {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "{}#{}", self.0, self.1)
+///     }
+/// }
+/// let error = FatError::new(Key("meshes", 17), std::fmt::Error);
+/// assert_eq!(
+///     error.to_string(),
+///     "Operating on 'meshes#17' failed with error an error occurred when formatting an argument"
+/// );
+/// assert_eq!(error.context().1, 17);
+/// let error = FatError::new("user's cache", std::fmt::Error);
+/// assert!(error.to_string().starts_with(r"Operating on 'user'\''s cache' failed"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FatError<E, C> {
+    context: C,
+    source: E,
+}
+
+impl<E, C> FatError<E, C> {
+    /// wrap `source` with the `context` it failed on
+    pub fn new(context: C, source: E) -> Self {
+        FatError { context, source }
+    }
+
+    /// the context the operation failed on
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+
+    /// the wrapped error
+    pub fn inner(&self) -> &E {
+        &self.source
+    }
+
+    /// take the wrapped error and the context out of the error
+    pub fn into_parts(self) -> (E, C) {
+        (self.source, self.context)
+    }
+}
+
+impl<E: Display, C: Display> Display for FatError<E, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "std")]
+        let context = crate::template::quote_text(self.context.to_string().into());
+        #[cfg(not(feature = "std"))]
+        let context = &self.context;
+        write!(
+            f,
+            "Operating on '{context}' failed with error {}",
+            self.source
+        )
+    }
+}
+
+impl<E: Error + 'static, C: Display + fmt::Debug> Error for FatError<E, C> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
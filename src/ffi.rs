@@ -0,0 +1,89 @@
+use crate::{display_chain, FatIOError};
+use std::error::Error;
+use std::ffi::c_char;
+
+/// copy `text` NUL-terminated into `buf`, truncated to `len - 1` bytes, returning the untruncated length without the NUL
+unsafe fn write_buffer(text: &str, buf: *mut c_char, len: usize) -> usize {
+    if !buf.is_null() && len > 0 {
+        let copied = text.len().min(len - 1);
+        std::ptr::copy_nonoverlapping(text.as_ptr(), buf.cast::<u8>(), copied);
+        *buf.add(copied) = 0;
+    }
+    text.len()
+}
+
+/// # Format the error and its causes into a caller-provided buffer
+/// Errors cross the boundary to C or C++ hosts as owned `*mut FatIOError` created with `Box::into_raw(Box::new(error))` and released with [`vg_fat_io_error_free`].
+/// The chain is joined by `: ` as UTF-8, truncated to `len - 1` bytes and NUL-terminated.
+/// Returns the length of the whole text without the NUL like `snprintf`, call it with a null `buf` to query the size.
+/// # Safety
+/// `error` must be a valid pointer from `Box::into_raw`, `buf` must be null or valid for writes of `len` bytes.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{vg_fat_io_error_format, vg_fat_io_error_free, FatIOError};
+/// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "plugin.toml".into());
+/// let error = Box::into_raw(Box::new(fat));
+/// let mut buf = [0 as std::ffi::c_char; 128];
+/// let len = unsafe { vg_fat_io_error_format(error, buf.as_mut_ptr(), buf.len()) };
+/// let text = unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) };
+/// assert_eq!(text.to_bytes().len(), len);
+/// assert!(text.to_str().unwrap().starts_with("Operating on file 'plugin.toml' failed"));
+/// unsafe { vg_fat_io_error_free(error) };
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn vg_fat_io_error_format(
+    error: *const FatIOError,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    write_buffer(&display_chain(&*error).to_string(), buf, len)
+}
+
+/// # Write the path of the error into a caller-provided buffer
+/// Same buffer handling as [`vg_fat_io_error_format`], paths which are not valid UTF-8 are converted lossily.
+/// # Safety
+/// `error` must be a valid pointer from `Box::into_raw`, `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn vg_fat_io_error_path(
+    error: *const FatIOError,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    write_buffer(&(*error).path().to_string_lossy(), buf, len)
+}
+
+/// # Write the io error kind like `NotFound` into a caller-provided buffer
+/// Same buffer handling as [`vg_fat_io_error_format`].
+/// # Safety
+/// `error` must be a valid pointer from `Box::into_raw`, `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn vg_fat_io_error_kind(
+    error: *const FatIOError,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    write_buffer(&format!("{:?}", (*error).kind()), buf, len)
+}
+
+/// # The raw OS error code of the error, `0` if there is none
+/// # Safety
+/// `error` must be a valid pointer from `Box::into_raw`.
+#[no_mangle]
+pub unsafe extern "C" fn vg_fat_io_error_os_code(error: *const FatIOError) -> i32 {
+    (*error)
+        .source()
+        .and_then(|e| e.downcast_ref::<std::io::Error>())
+        .and_then(std::io::Error::raw_os_error)
+        .unwrap_or(0)
+}
+
+/// # Release an error handed over from Rust
+/// Null pointers are ignored.
+/// # Safety
+/// `error` must be null or a pointer from `Box::into_raw` which was not released before.
+#[no_mangle]
+pub unsafe extern "C" fn vg_fat_io_error_free(error: *mut FatIOError) {
+    if !error.is_null() {
+        drop(Box::from_raw(error));
+    }
+}
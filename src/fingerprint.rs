@@ -0,0 +1,103 @@
+use crate::{chain, FatIOError};
+use std::error::Error;
+use std::path::{Component, Path};
+
+/// 64 bit FNV-1a, unlike the std hashers it is stable across releases and platforms
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        // separator, so that ("ab", "c") and ("a", "bc") differ
+        self.0 ^= 0xff;
+        self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+}
+
+/// replace every run of ascii digits by a single `#`
+fn strip_digits(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if !c.is_ascii_digit() {
+            result.push(c);
+        } else if !result.ends_with('#') {
+            result.push('#');
+        }
+    }
+    result
+}
+
+/// the path with volatile parts removed: the temp directory, names of temp files and numbers
+fn normalize_path(path: &Path) -> String {
+    let temp_dir = std::env::temp_dir();
+    let (prefix, rest) = match path.strip_prefix(&temp_dir) {
+        Ok(rest) => ("<tmp>", rest),
+        Err(_) => ("", path),
+    };
+    let mut normalized = prefix.to_string();
+    for component in rest.components() {
+        if !normalized.is_empty() {
+            normalized.push('/');
+        }
+        match component {
+            Component::Normal(name) => {
+                let name = name.to_string_lossy();
+                if name.starts_with(".tmp") {
+                    normalized.push_str(".tmp*");
+                } else {
+                    normalized.push_str(&strip_digits(&name));
+                }
+            }
+            other => normalized.push_str(&other.as_os_str().to_string_lossy()),
+        }
+    }
+    normalized
+}
+
+/// # A stable fingerprint of an error chain for grouping recurring failures
+/// The fingerprint covers the kinds of all io errors and the normalized paths of all [`FatIOError`]s in the chain, and the messages of other errors with numbers stripped.
+/// Paths are normalized by replacing the temp directory, temp file names (`.tmp…`) and numbers, so failures on different temp files or numbered outputs share one fingerprint.
+/// The OS specific message text of io errors is ignored. The value is stable across runs, platforms and releases of this crate with the same major version.
+/// # Examples
+/// ```rust
+/// use std::io::ErrorKind;
+/// use vg_errortools::{fingerprint, FatIOError};
+/// let temp = std::env::temp_dir();
+/// let first = FatIOError::from_std_io_err(ErrorKind::NotFound.into(), temp.join("export_17.tmp"));
+/// let second = FatIOError::from_std_io_err(ErrorKind::NotFound.into(), temp.join("export_4711.tmp"));
+/// let other = FatIOError::from_std_io_err(ErrorKind::PermissionDenied.into(), temp.join("export_17.tmp"));
+/// assert_eq!(fingerprint(&first), fingerprint(&second));
+/// assert_ne!(fingerprint(&first), fingerprint(&other));
+/// ```
+pub fn fingerprint(error: &(dyn Error + 'static)) -> u64 {
+    let mut hasher = Fnv1a::new();
+    for error in chain(error) {
+        let io = error.downcast_ref::<std::io::Error>();
+        let fat = error
+            .downcast_ref::<FatIOError>()
+            .or_else(|| io.and_then(FatIOError::from_io_error_payload));
+        if let Some(fat) = fat {
+            hasher.write(b"FatIOError");
+            hasher.write(format!("{:?}", fat.kind()).as_bytes());
+            hasher.write(normalize_path(fat.path()).as_bytes());
+        } else if let Some(io) = io {
+            hasher.write(b"io::Error");
+            hasher.write(format!("{:?}", io.kind()).as_bytes());
+        } else {
+            hasher.write(strip_digits(&error.to_string()).as_bytes());
+        }
+    }
+    hasher.0
+}
+
+/// [`fingerprint`] as 16 digit hex string, e.g. for a dashboard grouping key
+pub fn fingerprint_hex(error: &(dyn Error + 'static)) -> String {
+    format!("{:016x}", fingerprint(error))
+}
@@ -0,0 +1,97 @@
+use std::sync::RwLock;
+
+static APP_VERSION: RwLock<Option<String>> = RwLock::new(None);
+
+/// # Embed the application version together with OS and architecture in [`crate::MainError`] reports
+/// Once set, reports end with a `system:` line like `system: my-tool 1.4.2, linux x86_64`,
+/// so bug reports arriving as screenshots describe their environment. `None` removes the line again.
+/// Call this once at startup.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_app_version, MainError, MainErrorOptions};
+/// set_app_version(Some(concat!("my-tool ", env!("CARGO_PKG_VERSION")).to_string()));
+/// let error = MainError::from(std::io::Error::other("disk on fire"));
+/// let expected = format!("\nsystem: my-tool {}, {} {}", env!("CARGO_PKG_VERSION"), std::env::consts::OS, std::env::consts::ARCH);
+/// assert!(error.render(&MainErrorOptions::new()).ends_with(&expected));
+/// set_app_version(None);
+/// assert_eq!(error.render(&MainErrorOptions::new()), "disk on fire");
+/// ```
+pub fn set_app_version(version: Option<String>) {
+    *APP_VERSION.write().unwrap_or_else(|e| e.into_inner()) = version;
+}
+
+/// the version set by [`set_app_version`]
+pub fn app_version() -> Option<String> {
+    APP_VERSION
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// the application version, OS and architecture, if an application version is set
+pub(crate) fn system_info() -> Option<String> {
+    app_version().map(|version| {
+        format!(
+            "{version}, {} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )
+    })
+}
+
+static REPORT_FOOTER: RwLock<Option<String>> = RwLock::new(None);
+
+/// # Append a footer like "Please report this at …" to fatal errors
+/// Once set, the output of a [`crate::MainError`] returned from `main`, i.e. its `Debug` output, ends with the footer after an empty line.
+/// Reports rendered with [`crate::MainError::render`] are left alone. `None` removes the footer again.
+/// Call this once at startup.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_report_footer, MainError};
+/// set_report_footer(Some("Please report at https://example.com/issues with the text above".to_string()));
+/// let error = MainError::from(std::io::Error::other("disk on fire"));
+/// assert_eq!(
+///     format!("{error:?}"),
+///     "disk on fire\n\nPlease report at https://example.com/issues with the text above"
+/// );
+/// set_report_footer(None);
+/// ```
+pub fn set_report_footer(footer: Option<String>) {
+    *REPORT_FOOTER.write().unwrap_or_else(|e| e.into_inner()) = footer;
+}
+
+/// the footer set by [`set_report_footer`]
+pub fn report_footer() -> Option<String> {
+    REPORT_FOOTER
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+static END_USER_MESSAGE: RwLock<Option<String>> = RwLock::new(None);
+
+/// # Replace the message [`crate::Style::EndUser`] reports show instead of the cause chain
+/// Phrase it for your customers, e.g. naming the application and where to get help. `None` restores the default
+/// "The application ran into a problem and had to stop.". Call this once at startup.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_end_user_message, set_error_style, MainError, Style};
+/// set_error_style(Style::EndUser);
+/// set_end_user_message(Some("VGViewer ran into a problem and had to close.".to_string()));
+/// let error = MainError::from(std::io::Error::other("disk on fire"));
+/// let output = format!("{error:?}");
+/// assert!(output.starts_with("VGViewer ran into a problem and had to close.\n"));
+/// assert!(!output.contains("disk on fire"));
+/// ```
+pub fn set_end_user_message(message: Option<String>) {
+    *END_USER_MESSAGE.write().unwrap_or_else(|e| e.into_inner()) = message;
+}
+
+/// the message set by [`set_end_user_message`] or the default one
+pub fn end_user_message() -> String {
+    END_USER_MESSAGE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| "The application ran into a problem and had to stop.".to_string())
+}
@@ -0,0 +1,73 @@
+use crate::{chain, sanitized_chain, FatIOError};
+use std::error::Error;
+use std::io::ErrorKind;
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Status};
+
+/// the metadata key carrying the path of the first [`FatIOError`] in the chain
+pub const FILE_PATH_METADATA_KEY: &str = "x-file-path";
+
+/// # The gRPC code of an io error kind
+/// `NotFound`, `PermissionDenied` and `AlreadyExists` map to their namesakes, `InvalidInput`/`InvalidData` to `InvalidArgument`,
+/// `TimedOut` to `DeadlineExceeded`, connection and transient errors to `Unavailable`, `Unsupported` to `Unimplemented`
+/// and everything else to `Internal`.
+pub fn grpc_code_of_kind(kind: ErrorKind) -> Code {
+    match kind {
+        ErrorKind::NotFound => Code::NotFound,
+        ErrorKind::PermissionDenied => Code::PermissionDenied,
+        ErrorKind::AlreadyExists => Code::AlreadyExists,
+        ErrorKind::InvalidInput | ErrorKind::InvalidData => Code::InvalidArgument,
+        ErrorKind::TimedOut => Code::DeadlineExceeded,
+        ErrorKind::ConnectionRefused
+        | ErrorKind::ConnectionReset
+        | ErrorKind::ConnectionAborted
+        | ErrorKind::NotConnected
+        | ErrorKind::BrokenPipe
+        | ErrorKind::WouldBlock
+        | ErrorKind::Interrupted => Code::Unavailable,
+        ErrorKind::Unsupported => Code::Unimplemented,
+        _ => Code::Internal,
+    }
+}
+
+/// # Convert an error chain into a [`tonic::Status`]
+/// The code is taken from the first io error or [`FatIOError`] in the chain, `Internal` if there is none.
+/// The message joins the messages of [`sanitized_chain`] with `: `, so it names files only, like HTTP responses.
+/// The full path of the first `FatIOError` is attached as [`FILE_PATH_METADATA_KEY`] metadata if it is valid ASCII, drop it before the status crosses a trust boundary.
+/// # Examples
+/// ```rust
+/// use tonic::Code;
+/// use vg_errortools::{grpc_status_of, FatIOError, MainError};
+/// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "volumes/ct.raw".into());
+/// let error: MainError = fat.into();
+/// let status = grpc_status_of(error.error());
+/// assert_eq!(status.code(), Code::NotFound);
+/// assert_eq!(status.message(), "Operating on file 'ct.raw' failed with error entity not found: entity not found");
+/// assert_eq!(status.metadata().get("x-file-path").unwrap(), "volumes/ct.raw");
+/// ```
+pub fn grpc_status_of(error: &(dyn Error + 'static)) -> Status {
+    let code = chain(error)
+        .find_map(|e| {
+            e.downcast_ref::<FatIOError>()
+                .map(FatIOError::kind)
+                .or_else(|| e.downcast_ref::<std::io::Error>().map(std::io::Error::kind))
+        })
+        .map_or(Code::Internal, grpc_code_of_kind);
+    let message = sanitized_chain(error).join(": ");
+    let mut status = Status::new(code, message);
+    let path = chain(error).find_map(|e| e.downcast_ref::<FatIOError>());
+    if let Some(value) = path
+        .and_then(|fat| fat.path().to_str())
+        .and_then(|path| MetadataValue::try_from(path).ok())
+    {
+        status.metadata_mut().insert(FILE_PATH_METADATA_KEY, value);
+    }
+    status
+}
+
+/// see [`grpc_status_of`]
+impl From<FatIOError> for Status {
+    fn from(e: FatIOError) -> Self {
+        grpc_status_of(&e)
+    }
+}
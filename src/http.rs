@@ -0,0 +1,306 @@
+use crate::report::write_json_string;
+use crate::template::display_path;
+use crate::{chain, FatCopyError, FatIOError, FatRenameError, FatSearchError, MainError};
+use std::error::Error;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// # The HTTP status code an error translates to at a handler boundary
+/// The io error kinds map as follows, everything else is `500 Internal Server Error`:
+///
+/// | kind | status |
+/// |------|--------|
+/// | `NotFound` | 404 |
+/// | `PermissionDenied` | 403 |
+/// | `AlreadyExists` | 409 |
+/// | `InvalidInput`, `InvalidData` | 400 |
+/// | `TimedOut` | 504 |
+///
+/// Implement it for your own errors to take part in [`http_status_of`].
+/// # Examples
+/// ```rust
+/// use std::io::ErrorKind;
+/// use vg_errortools::{FatIOError, HttpStatus, MainError};
+/// let fat = FatIOError::from_std_io_err(ErrorKind::NotFound.into(), "assets/logo.png".into());
+/// assert_eq!(fat.http_status(), 404);
+/// let error = MainError::from(fat);
+/// assert_eq!(error.http_status(), 404);
+/// ```
+pub trait HttpStatus {
+    /// the HTTP status code
+    fn http_status(&self) -> u16;
+}
+
+/// the HTTP status code of an io error kind, see [`HttpStatus`]
+pub fn http_status_of_kind(kind: ErrorKind) -> u16 {
+    match kind {
+        ErrorKind::NotFound => 404,
+        ErrorKind::PermissionDenied => 403,
+        ErrorKind::AlreadyExists => 409,
+        ErrorKind::InvalidInput | ErrorKind::InvalidData => 400,
+        ErrorKind::TimedOut => 504,
+        _ => 500,
+    }
+}
+
+/// the HTTP status of the first io error or [`FatIOError`] in the chain, `500` if there is none
+pub fn http_status_of(error: &(dyn Error + 'static)) -> u16 {
+    chain(error)
+        .find_map(|e| {
+            e.downcast_ref::<FatIOError>()
+                .map(FatIOError::kind)
+                .or_else(|| e.downcast_ref::<std::io::Error>().map(std::io::Error::kind))
+        })
+        .map_or(500, http_status_of_kind)
+}
+
+impl HttpStatus for std::io::Error {
+    fn http_status(&self) -> u16 {
+        http_status_of_kind(self.kind())
+    }
+}
+
+impl HttpStatus for FatIOError {
+    fn http_status(&self) -> u16 {
+        http_status_of_kind(self.kind())
+    }
+}
+
+impl<R> HttpStatus for MainError<R> {
+    fn http_status(&self) -> u16 {
+        http_status_of(self.error())
+    }
+}
+
+/// the paths carried by `error` if it is one of the path-carrying errors of this crate
+fn paths_of<'a>(error: &'a (dyn Error + 'static)) -> Vec<&'a Path> {
+    if let Some(fat) = error.downcast_ref::<FatIOError>() {
+        return vec![fat.path()];
+    }
+    if let Some(copy) = error.downcast_ref::<FatCopyError>() {
+        return vec![copy.from_path(), copy.to_path()];
+    }
+    if let Some(rename) = error.downcast_ref::<FatRenameError>() {
+        return vec![rename.from_path(), rename.to_path()];
+    }
+    if let Some(search) = error.downcast_ref::<FatSearchError>() {
+        return search.attempts().map(|(path, _)| path).collect();
+    }
+    #[cfg(feature = "config")]
+    if let Some(config) = error.downcast_ref::<crate::FatConfigError>() {
+        return vec![config.path()];
+    }
+    #[cfg(feature = "mmap")]
+    if let Some(map) = error.downcast_ref::<crate::FatMapError>() {
+        return vec![map.path()];
+    }
+    #[cfg(feature = "fs4")]
+    if let Some(space) = error.downcast_ref::<crate::FatSpaceError>() {
+        return vec![space.path()];
+    }
+    Vec::new()
+}
+
+/// # The messages of an error chain safe to send to clients
+/// Paths of the path-carrying errors of this crate in the chain, like [`FatIOError`], [`FatCopyError`] or [`FatSearchError`],
+/// are reduced to their file name in all messages, so server side directory layouts don't leak.
+/// # Examples
+/// ```rust
+/// use std::io::ErrorKind;
+/// use vg_errortools::{fat_copy_with_progress, sanitized_chain, FatIOError};
+/// let fat = FatIOError::from_std_io_err(ErrorKind::NotFound.into(), "/srv/data/assets/logo.png".into());
+/// assert_eq!(
+///     sanitized_chain(&fat),
+///     ["Operating on file 'logo.png' failed with error entity not found", "entity not found"]
+/// );
+/// let copy = fat_copy_with_progress("/srv/data/missing.png", "/srv/backup/missing.png", |_, _| {}).unwrap_err();
+/// let message = &sanitized_chain(&copy)[0];
+/// assert!(message.starts_with("copying 'missing.png' to 'missing.png' failed at byte 0 while reading the source: "));
+/// ```
+pub fn sanitized_chain(error: &(dyn Error + 'static)) -> Vec<String> {
+    let mut replacements: Vec<(String, String)> = chain(error)
+        .flat_map(paths_of)
+        .map(|path| {
+            let full = display_path(path).into_owned();
+            let name = path
+                .file_name()
+                .map(|name| display_path(Path::new(name)).into_owned())
+                .unwrap_or_default();
+            (full, name)
+        })
+        .filter(|(full, name)| full != name)
+        .collect();
+    // longer paths first, so a parent directory does not shadow the paths below it
+    replacements.sort_by_key(|(full, _)| std::cmp::Reverse(full.len()));
+    chain(error)
+        .map(|e| {
+            replacements
+                .iter()
+                .fold(e.to_string(), |message, (full, name)| {
+                    message.replace(full.as_str(), name)
+                })
+        })
+        .collect()
+}
+
+/// # The body format of error responses
+/// Used by the `IntoResponse` and `ResponseError` implementations of the `axum` and `actix` features, both carry the [`sanitized_chain`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ResponseBody {
+    /// `{"status":404,"error":"…","causes":["…"]}` as `application/json`, the default
+    #[default]
+    Json,
+    /// the message and one `caused by:` line per cause as `text/plain`
+    Text,
+}
+
+static RESPONSE_BODY: AtomicU8 = AtomicU8::new(ResponseBody::Json as u8);
+
+/// # Select the process wide body format of error responses
+/// Call this once at startup.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{response_body, set_response_body, ResponseBody};
+/// set_response_body(ResponseBody::Text);
+/// assert_eq!(response_body(), ResponseBody::Text);
+/// ```
+pub fn set_response_body(body: ResponseBody) {
+    RESPONSE_BODY.store(body as u8, Ordering::Relaxed);
+}
+
+/// the process wide body format of error responses, see [`set_response_body`]
+pub fn response_body() -> ResponseBody {
+    match RESPONSE_BODY.load(Ordering::Relaxed) {
+        1 => ResponseBody::Text,
+        _ => ResponseBody::Json,
+    }
+}
+
+impl ResponseBody {
+    /// the content type and body of an error response
+    #[cfg_attr(not(any(feature = "axum", feature = "actix")), allow(dead_code))]
+    pub(crate) fn render(
+        self,
+        status: u16,
+        error: &(dyn Error + 'static),
+    ) -> (&'static str, String) {
+        let mut messages = sanitized_chain(error).into_iter();
+        let message = messages.next().unwrap_or_default();
+        match self {
+            ResponseBody::Json => {
+                let mut body = format!("{{\"status\":{status},\"error\":");
+                let _ = write_json_string(&mut body, &message);
+                body.push_str(",\"causes\":[");
+                for (i, cause) in messages.enumerate() {
+                    if i > 0 {
+                        body.push(',');
+                    }
+                    let _ = write_json_string(&mut body, &cause);
+                }
+                body.push_str("]}");
+                ("application/json", body)
+            }
+            ResponseBody::Text => {
+                let body = messages.fold(message, |body, cause| body + "\ncaused by: " + &cause);
+                ("text/plain; charset=utf-8", body)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use super::{response_body, HttpStatus};
+    use crate::{FatIOError, MainError};
+    use axum_core::body::Body;
+    use axum_core::response::{IntoResponse, Response};
+    use http::{header, StatusCode};
+    use std::error::Error;
+
+    fn error_response(status: u16, error: &(dyn Error + 'static)) -> Response {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let (content_type, body) = response_body().render(status.as_u16(), error);
+        let mut response = Response::new(Body::from(body));
+        *response.status_mut() = status;
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static(content_type),
+        );
+        response
+    }
+
+    /// Responds with the [`HttpStatus`] of the error and the sanitized chain as [`crate::ResponseBody`]
+    impl IntoResponse for FatIOError {
+        fn into_response(self) -> Response {
+            error_response(self.http_status(), &self)
+        }
+    }
+
+    /// Responds with the [`HttpStatus`] of the error and the sanitized chain as [`crate::ResponseBody`]
+    /// # Examples
+    /// ```rust
+    /// use axum_core::response::IntoResponse;
+    /// use vg_errortools::{FatIOError, JsonReport, MainError};
+    /// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "/srv/logo.png".into());
+    /// let response = MainError::from(fat).with_style::<JsonReport>().into_response();
+    /// assert_eq!(response.status(), 404);
+    /// ```
+    impl<R> IntoResponse for MainError<R> {
+        fn into_response(self) -> Response {
+            error_response(self.http_status(), self.error())
+        }
+    }
+}
+
+#[cfg(feature = "actix")]
+mod actix {
+    use super::{response_body, HttpStatus};
+    use crate::{FatIOError, MainError, ReportStyle};
+    use actix_web::http::header::{self, HeaderValue};
+    use actix_web::http::StatusCode;
+    use actix_web::{HttpResponse, ResponseError};
+    use std::error::Error;
+
+    fn error_response(status: StatusCode, error: &(dyn Error + 'static)) -> HttpResponse {
+        let (content_type, body) = response_body().render(status.as_u16(), error);
+        HttpResponse::build(status)
+            .insert_header((header::CONTENT_TYPE, HeaderValue::from_static(content_type)))
+            .body(body)
+    }
+
+    fn status_code(status: u16) -> StatusCode {
+        StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    /// Responds with the [`HttpStatus`] of the error and the sanitized chain as [`crate::ResponseBody`]
+    impl ResponseError for FatIOError {
+        fn status_code(&self) -> StatusCode {
+            status_code(self.http_status())
+        }
+
+        fn error_response(&self) -> HttpResponse {
+            error_response(self.status_code(), self)
+        }
+    }
+
+    /// Responds with the [`HttpStatus`] of the error and the sanitized chain as [`crate::ResponseBody`]
+    /// # Examples
+    /// ```rust
+    /// use actix_web::ResponseError;
+    /// use vg_errortools::{FatIOError, MainError};
+    /// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::PermissionDenied.into(), "/srv/logo.png".into());
+    /// let response = MainError::from(fat).error_response();
+    /// assert_eq!(response.status(), 403);
+    /// ```
+    impl<R: ReportStyle> ResponseError for MainError<R> {
+        fn status_code(&self) -> StatusCode {
+            status_code(self.http_status())
+        }
+
+        fn error_response(&self) -> HttpResponse {
+            error_response(self.status_code(), self.error())
+        }
+    }
+}
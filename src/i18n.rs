@@ -0,0 +1,243 @@
+use crate::{canonical, fat_io_wrap_std, FatIOError};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter, Write};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::RwLock;
+
+/// # A catalog of translated standard phrases
+/// The phrases are read from the simple message form of Fluent (`.ftl`) files, see [`Catalog::parse_ftl`] for the supported subset.
+/// Select a catalog with [`set_catalog`], phrases missing in it stay English. The keys are:
+///
+/// | key | arguments | English |
+/// |-----|-----------|---------|
+/// | `fat-io-error` | `path`, `error` | `Operating on file '{ $path }' failed with error { $error }` |
+/// | `fat-io-operation-error` | `operation`, `path`, `error` | `{ $operation } on '{ $path }' failed with error { $error }` |
+/// | `caused-by` | | `caused by:` |
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_catalog, Catalog, FatIOError, MainError, MainErrorOptions};
+/// let german = Catalog::parse_ftl(
+///     "# Deutsch\n\
+///      fat-io-error = Bearbeiten der Datei '{ $path }' ist fehlgeschlagen: { $error }\n\
+///      caused-by = verursacht durch:\n",
+/// )
+/// .unwrap();
+/// set_catalog(Some(german));
+/// let fat = FatIOError::from_std_io_err(std::io::Error::other("Platte brennt"), "scan.raw".into());
+/// let error = MainError::from(fat);
+/// assert_eq!(
+///     error.render(&MainErrorOptions::new()),
+///     "Bearbeiten der Datei 'scan.raw' ist fehlgeschlagen: Platte brennt\nverursacht durch: Platte brennt\ncode: VG-IO-0000"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// an empty catalog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Parse the messages of a Fluent file
+    /// Only this subset of Fluent is supported, anything else is rejected with an [`FtlError`]:
+    /// - comments starting with `#` at the beginning of a line and blank lines
+    /// - messages `key = value`, keys are Fluent identifiers like `fat-io-error`
+    /// - values continued on indented lines, joined with line breaks and without their indentation
+    /// - placeables `{ $name }` referring to an argument, literal braces are not supported
+    ///
+    /// Terms, attributes, select expressions and references to other messages are not supported.
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::Catalog;
+    /// let catalog = Catalog::parse_ftl("caused-by =\n    verursacht\n    durch:\n").unwrap();
+    /// assert_eq!(catalog.format("caused-by", &[]).unwrap(), "verursacht\ndurch:");
+    /// let error = Catalog::parse_ftl("caused-by = verursacht durch:\n    .title = Ursache\n").unwrap_err();
+    /// assert_eq!(error.line(), 2);
+    /// ```
+    pub fn parse_ftl(source: &str) -> Result<Self, FtlError> {
+        let mut catalog = Catalog::new();
+        let mut current: Option<String> = None;
+        let mut blank_lines = 0;
+        for (index, line) in source.lines().enumerate() {
+            let error = |reason| FtlError {
+                line: index + 1,
+                reason,
+            };
+            if line.trim().is_empty() {
+                blank_lines += 1;
+                continue;
+            }
+            if line.starts_with('#') {
+                current = None;
+                continue;
+            }
+            if line.starts_with(char::is_whitespace) {
+                let message = current
+                    .as_ref()
+                    .and_then(|key| catalog.messages.get_mut(key))
+                    .ok_or(error("indented line outside of a message"))?;
+                let text = line.trim();
+                if text.starts_with('.') {
+                    return Err(error("attributes are not supported"));
+                }
+                if text.starts_with(['[', '*']) {
+                    return Err(error("select expressions are not supported"));
+                }
+                check_placeables(text).map_err(error)?;
+                if !message.is_empty() {
+                    message.extend(std::iter::repeat_n('\n', blank_lines + 1));
+                }
+                message.push_str(text);
+                blank_lines = 0;
+                continue;
+            }
+            blank_lines = 0;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(error("expected a message `key = value`"))?;
+            let key = key.trim();
+            if key.starts_with('-') {
+                return Err(error("terms are not supported"));
+            }
+            if !is_identifier(key) {
+                return Err(error("the message key is no identifier"));
+            }
+            let value = value.trim();
+            check_placeables(value).map_err(error)?;
+            catalog.insert(key, value);
+            current = Some(key.to_string());
+        }
+        Ok(catalog)
+    }
+
+    /// read and parse a Fluent file, see [`Catalog::parse_ftl`], unsupported content fails with kind `InvalidData` wrapping the [`FtlError`]
+    pub fn load_ftl(path: impl AsRef<Path>) -> Result<Self, FatIOError> {
+        let path = path.as_ref();
+        let source = fat_io_wrap_std(path, &std::fs::read_to_string)?;
+        Catalog::parse_ftl(&source).map_err(|e| {
+            FatIOError::from_shared_path(
+                std::io::Error::new(ErrorKind::InvalidData, e),
+                canonical::recorded_path(path),
+            )
+        })
+    }
+
+    /// add or replace the phrase for `key`
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.messages.insert(key.into(), value.into());
+        self
+    }
+
+    /// # The phrase for `key` with its arguments substituted
+    /// Placeables are substituted in a single pass, so `{ $name }` inside an argument value is kept as is.
+    /// Placeables without a matching argument are kept as well.
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::Catalog;
+    /// let catalog = Catalog::parse_ftl("fat-io-error = { $path }: { $error }").unwrap();
+    /// let text = catalog.format("fat-io-error", &[("path", &"{ $error }.raw"), ("error", &"gone")]);
+    /// assert_eq!(text.unwrap(), "{ $error }.raw: gone");
+    /// ```
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> Option<String> {
+        let mut rest = self.messages.get(key)?.as_str();
+        let mut text = String::with_capacity(rest.len());
+        while let Some((start, end)) = rest
+            .find('{')
+            .and_then(|start| Some((start, start + rest[start..].find('}')?)))
+        {
+            text.push_str(&rest[..start]);
+            let placeable = &rest[start..=end];
+            let value = placeable[1..placeable.len() - 1]
+                .trim()
+                .strip_prefix('$')
+                .and_then(|name| args.iter().find(|(arg, _)| *arg == name));
+            match value {
+                Some((_, value)) => {
+                    let _ = write!(text, "{value}");
+                }
+                None => text.push_str(placeable),
+            }
+            rest = &rest[end + 1..];
+        }
+        text.push_str(rest);
+        Some(text)
+    }
+}
+
+/// # A line of a Fluent file outside the subset supported by [`Catalog::parse_ftl`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FtlError {
+    line: usize,
+    reason: &'static str,
+}
+
+impl FtlError {
+    /// the line number, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// why the line is not supported
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl Display for FtlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unsupported Fluent syntax in line {}: {}",
+            self.line, self.reason
+        )
+    }
+}
+
+impl Error for FtlError {}
+
+/// whether `key` is a Fluent identifier, a letter followed by letters, digits, `-` and `_`
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// accept only `{ $name }` placeables with balanced braces
+fn check_placeables(text: &str) -> Result<(), &'static str> {
+    let mut rest = text;
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return Err("`}` outside of a placeable");
+        }
+        let end = start + rest[start..].find('}').ok_or("unclosed placeable")?;
+        let inner = rest[start + 1..end].trim();
+        if !inner.strip_prefix('$').is_some_and(is_identifier) {
+            return Err("only `{ $name }` placeables are supported");
+        }
+        rest = &rest[end + 1..];
+    }
+    Ok(())
+}
+
+static CATALOG: RwLock<Option<Catalog>> = RwLock::new(None);
+
+/// # Select the process wide [`Catalog`], `None` restores the English phrases
+/// Call this once at startup, e.g. with the catalog of the UI language.
+pub fn set_catalog(catalog: Option<Catalog>) {
+    *CATALOG.write().unwrap_or_else(|e| e.into_inner()) = catalog;
+}
+
+/// the phrase for `key` from the selected catalog, `None` if there is none
+pub(crate) fn phrase(key: &str, args: &[(&str, &dyn Display)]) -> Option<String> {
+    CATALOG
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()?
+        .format(key, args)
+}
@@ -0,0 +1,87 @@
+use crate::{canonical, capture, capture_options, FatIOError};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// # An opt-in interner for paths of failed operations
+/// Repeated failures on the same file share one allocation of the path instead of copying it every time.
+/// Use a dedicated interner or the process wide [`PathInterner::global`] together with [`fat_io_wrap_std_interned`].
+/// The interner never forgets paths on its own, call [`PathInterner::clear`] if the set of files changes over time.
+#[derive(Debug, Default)]
+pub struct PathInterner {
+    paths: Mutex<HashSet<Arc<Path>>>,
+}
+
+impl PathInterner {
+    /// create a new, empty interner
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the process wide interner
+    pub fn global() -> &'static PathInterner {
+        static GLOBAL: OnceLock<PathInterner> = OnceLock::new();
+        GLOBAL.get_or_init(PathInterner::new)
+    }
+
+    /// get the shared handle for `path`, copying it only on the first request
+    /// # Examples
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use vg_errortools::PathInterner;
+    /// let interner = PathInterner::new();
+    /// let first = interner.intern("locked.txt".as_ref());
+    /// let second = interner.intern("locked.txt".as_ref());
+    /// assert!(Arc::ptr_eq(&first, &second));
+    /// ```
+    pub fn intern(&self, path: &Path) -> Arc<Path> {
+        let mut paths = self.paths.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(interned) = paths.get(path) {
+            return interned.clone();
+        }
+        let interned: Arc<Path> = Arc::from(path);
+        paths.insert(interned.clone());
+        interned
+    }
+
+    /// number of distinct paths currently held
+    pub fn len(&self) -> usize {
+        self.paths.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// true if no path is held
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// drop all held paths, handles given out before stay valid
+    pub fn clear(&self) {
+        self.paths.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+/// # Wrapper for std::io functions using a [`PathInterner`]
+/// Same as [`crate::fat_io_wrap_std`], but the path stored in the error is taken from the interner, so failing repeatedly on the same file does not allocate again.
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+/// use vg_errortools::{fat_io_wrap_std_interned, PathInterner};
+/// for _ in 0..3 {
+///     assert!(fat_io_wrap_std_interned("does/not/exist.txt", &File::open, PathInterner::global()).is_err());
+/// }
+/// assert_eq!(PathInterner::global().len(), 1);
+/// ```
+pub fn fat_io_wrap_std_interned<'a, T, P: AsRef<Path> + ?Sized>(
+    path: &'a P,
+    f: &dyn Fn(&'a P) -> std::io::Result<T>,
+    interner: &PathInterner,
+) -> Result<T, FatIOError> {
+    let started = capture::start_timer();
+    f(path).map_err(|e| {
+        let path = match capture_options().captures_canonical_path() {
+            true => canonical::recorded_path(path.as_ref()),
+            false => interner.intern(path.as_ref()),
+        };
+        FatIOError::from_shared_path(e, path).with_elapsed(started)
+    })
+}
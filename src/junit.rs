@@ -0,0 +1,114 @@
+use crate::{chain, fat_io_wrap_std, FatIOError, MainError};
+use std::error::Error;
+use std::fmt::Write;
+use std::path::Path;
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' | '\r' | '\t' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// # A minimal JUnit XML report of failed checks
+/// Every failure becomes a test case with a `failure` element, its message is the error and its text the whole cause chain one per line.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{FatIOError, JUnitReport};
+/// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "scans/part<7>.raw".into());
+/// let mut report = JUnitReport::new("validation");
+/// report.add_failure("part 7", &fat);
+/// let xml = report.to_xml();
+/// assert!(xml.contains(r#"<testsuite name="validation" tests="1" failures="1">"#));
+/// assert!(xml.contains(r#"<failure message="Operating on file 'scans/part&lt;7&gt;.raw' failed"#));
+/// ```
+#[derive(Debug, Clone)]
+pub struct JUnitReport {
+    suite: String,
+    cases: Vec<(String, Option<(String, String)>)>,
+}
+
+impl JUnitReport {
+    /// an empty report for the test suite `suite`
+    pub fn new(suite: impl Into<String>) -> Self {
+        JUnitReport {
+            suite: suite.into(),
+            cases: Vec::new(),
+        }
+    }
+
+    /// add a passed test case
+    pub fn add_success(&mut self, case: impl Into<String>) -> &mut Self {
+        self.cases.push((case.into(), None));
+        self
+    }
+
+    /// add a test case failed with `error`
+    pub fn add_failure(
+        &mut self,
+        case: impl Into<String>,
+        error: &(dyn Error + 'static),
+    ) -> &mut Self {
+        let causes: Vec<String> = chain(error).map(|e| e.to_string()).collect();
+        self.cases.push((
+            case.into(),
+            Some((error.to_string(), causes.join("\ncaused by: "))),
+        ));
+        self
+    }
+
+    /// add a test case failed with the error of a [`MainError`]
+    pub fn add_main_error(&mut self, case: impl Into<String>, error: &MainError) -> &mut Self {
+        self.add_failure(case, error.error())
+    }
+
+    /// number of failed test cases
+    pub fn failures(&self) -> usize {
+        self.cases.iter().filter(|(_, f)| f.is_some()).count()
+    }
+
+    /// the report as JUnit XML document
+    pub fn to_xml(&self) -> String {
+        let suite = escape_xml(&self.suite);
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{suite}\" tests=\"{}\" failures=\"{}\">",
+            self.cases.len(),
+            self.failures()
+        );
+        for (case, failure) in &self.cases {
+            let case = escape_xml(case);
+            match failure {
+                None => {
+                    let _ = writeln!(xml, "    <testcase name=\"{case}\" classname=\"{suite}\"/>");
+                }
+                Some((message, text)) => {
+                    let _ = writeln!(
+                        xml,
+                        "    <testcase name=\"{case}\" classname=\"{suite}\">\n      <failure message=\"{}\">{}</failure>\n    </testcase>",
+                        escape_xml(message),
+                        escape_xml(text)
+                    );
+                }
+            }
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+
+    /// write the report to `path`, e.g. for ingestion by CI test report tooling
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), FatIOError> {
+        let xml = self.to_xml();
+        fat_io_wrap_std(path.as_ref(), &|path| std::fs::write(path, &xml))
+    }
+}
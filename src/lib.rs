@@ -4,52 +4,608 @@
 #![warn(missing_docs)]
 #![warn(unused_qualifications)]
 #![deny(deprecated)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use std::error::Error;
-use std::fmt;
+use core::error::Error;
+#[cfg(feature = "std")]
+use core::fmt;
+#[cfg(feature = "std")]
+use std::backtrace::Backtrace;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+#[cfg(feature = "std")]
 use std::fmt::{Debug, Display, Formatter};
 #[cfg(feature = "tokio")]
 use std::future::Future;
+#[cfg(feature = "std")]
+use std::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::panic::Location;
+#[cfg(feature = "std")]
 use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
+use std::sync::atomic::AtomicBool;
+#[cfg(feature = "std")]
+use std::sync::{Arc, OnceLock};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant, SystemTime};
+
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "std")]
+mod call;
+#[cfg(feature = "std")]
+mod canonical;
+#[cfg(feature = "std")]
+mod capture;
+#[cfg(feature = "std")]
+mod channel;
+#[cfg(feature = "std")]
+mod code;
+#[cfg(feature = "config")]
+mod config;
+#[cfg(feature = "wasm")]
+mod console;
+mod context;
+#[cfg(feature = "std")]
+mod copy;
+#[cfg(feature = "std")]
+mod crash;
+#[cfg(feature = "std")]
+mod create;
+#[cfg(feature = "diagnose")]
+mod diagnose;
+mod fat;
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(feature = "std")]
+mod fingerprint;
+#[cfg(feature = "std")]
+mod footer;
+#[cfg(feature = "tonic")]
+mod grpc;
+#[cfg(feature = "std")]
+mod http;
+#[cfg(feature = "i18n")]
+mod i18n;
+#[cfg(feature = "std")]
+mod intern;
+#[cfg(feature = "std")]
+mod junit;
+#[cfg(feature = "std")]
+mod limit;
+#[cfg(feature = "std")]
+mod lock;
+#[cfg(feature = "log")]
+mod logging;
+#[cfg(feature = "mmap")]
+mod mapping;
+#[cfg(feature = "unicode")]
+mod normalize;
+#[cfg(feature = "otel")]
+mod otel;
+#[cfg(feature = "std")]
+mod panic;
+#[cfg(feature = "std")]
+mod pipe;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "std")]
+mod rename;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+mod reporter;
+#[cfg(feature = "std")]
+mod result;
+#[cfg(feature = "std")]
+mod retry;
+#[cfg(feature = "std")]
+mod run;
+#[cfg(feature = "std")]
+mod rw;
+#[cfg(feature = "std")]
+mod scope;
+#[cfg(feature = "std")]
+mod search;
+#[cfg(feature = "sentry")]
+mod sentry;
+#[cfg(feature = "signals")]
+mod signal;
+#[cfg(feature = "std")]
+mod sink;
+#[cfg(feature = "fs4")]
+mod space;
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(any(feature = "log", feature = "tracing"))]
+mod tap;
+#[cfg(feature = "std")]
+mod template;
+#[cfg(feature = "test-util")]
+mod testing;
+#[cfg(feature = "std")]
+mod thread;
+#[cfg(feature = "tracing")]
+mod trace;
+#[cfg(feature = "camino")]
+mod utf8_path;
+#[cfg(feature = "digest")]
+mod verify;
+#[cfg(feature = "tokio")]
+pub use async_io::{
+    fat_join, fat_read_dir, fat_spawn, fat_spawn_blocking, fat_tokio_copy, fat_tokio_rename,
+    fat_tokio_write, FatAsyncReader, FatAsyncWriter, FatReadDir, FatTaskError,
+};
+#[cfg(feature = "std")]
+pub use call::ErrorContext;
+#[cfg(feature = "std")]
+pub use canonical::fat_io_wrap_std_canonical;
+#[cfg(feature = "std")]
+pub use capture::{
+    capture_options, set_capture_options, CaptureOptions, Environment, FileMetadata, ThreadInfo,
+};
+#[cfg(feature = "std")]
+pub use channel::{ChannelError, ChannelResultExt, FatChannelError};
+#[cfg(feature = "std")]
+pub use code::{
+    documentation_url_template, error_code_of, register_error_code, set_documentation_url_template,
+    ErrorCode,
+};
+#[cfg(feature = "config")]
+pub use config::{fat_read_json_config, FatConfigError};
+#[cfg(feature = "wasm")]
+pub use console::{install_console_error_hook, ConsoleSink};
+pub use context::{display_chain, ContextError, ContextResultExt, DisplayChain};
+#[cfg(feature = "std")]
+pub use copy::{fat_copy_with_progress, CopySide, FatCopyError};
+#[cfg(feature = "std")]
+pub use crash::{crash_report_dir, set_crash_report_dir};
+#[cfg(feature = "std")]
+pub use create::{fat_create_new, FileExists};
+#[cfg(feature = "diagnose")]
+pub use diagnose::diagnose;
+pub use fat::FatError;
+#[cfg(feature = "ffi")]
+pub use ffi::{
+    vg_fat_io_error_format, vg_fat_io_error_free, vg_fat_io_error_kind, vg_fat_io_error_os_code,
+    vg_fat_io_error_path,
+};
+#[cfg(feature = "std")]
+pub use fingerprint::{fingerprint, fingerprint_hex};
+#[cfg(feature = "std")]
+pub use footer::{
+    app_version, end_user_message, report_footer, set_app_version, set_end_user_message,
+    set_report_footer,
+};
+#[cfg(feature = "tonic")]
+pub use grpc::{grpc_code_of_kind, grpc_status_of, FILE_PATH_METADATA_KEY};
+#[cfg(feature = "std")]
+pub use http::{
+    http_status_of, http_status_of_kind, response_body, sanitized_chain, set_response_body,
+    HttpStatus, ResponseBody,
+};
+#[cfg(feature = "i18n")]
+pub use i18n::{set_catalog, Catalog, FtlError};
+#[cfg(feature = "std")]
+pub use intern::{fat_io_wrap_std_interned, PathInterner};
+#[cfg(feature = "std")]
+pub use junit::JUnitReport;
+#[cfg(feature = "std")]
+pub use limit::{fat_read_limited, SizeLimitExceeded};
+#[cfg(feature = "std")]
+pub use lock::{fat_lock, fat_read_lock, fat_write_lock, FatLockError};
+#[cfg(feature = "log")]
+pub use logging::log_error_chain;
+#[cfg(feature = "mmap")]
+pub use mapping::{fat_map, fat_map_range, FatMapError};
+#[cfg(feature = "unicode")]
+pub use normalize::{nfc_paths, set_nfc_paths};
+#[cfg(feature = "otel")]
+pub use otel::{
+    exception_attributes, record_exception, record_exception_on_active_span, record_main_error,
+};
+#[cfg(feature = "std")]
+pub use panic::{catch_into_main_error, install_panic_hook, PanicError};
+#[cfg(feature = "std")]
+pub use pipe::{broken_pipe_exit_code, set_broken_pipe_exit_code};
+#[cfg(feature = "python")]
+pub use python::{add_exception_types, PyFatIOError, PyMainError};
+#[cfg(feature = "std")]
+pub use rename::{fat_rename_or_copy, FatRenameError};
+#[cfg(feature = "std")]
+pub use report::{
+    error_style, set_error_style, ColorChoice, MainErrorOptions, OutputStream, Style, Verbosity,
+    VERBOSITY_ENV_VAR,
+};
+#[cfg(feature = "std")]
+use report::{Detail, Report};
+#[cfg(feature = "std")]
+pub use reporter::{
+    set_reporter, CompactReport, ConfiguredReport, EndUserReport, FancyReport, JsonReport,
+    NumberedReport, PlainReport, ReportStyle, Reporter,
+};
+#[cfg(feature = "std")]
+pub use result::FatResultExt;
+#[cfg(feature = "std")]
+pub use retry::{
+    fat_io_wrap_std_retry_interrupted, fat_io_wrap_std_would_block, WouldBlockStrategy,
+    INTERRUPTED_RETRIES,
+};
+#[cfg(feature = "std")]
+pub use run::run_main;
+#[cfg(feature = "tokio")]
+pub use run::run_main_async;
+#[cfg(feature = "std")]
+pub use rw::{FatReader, FatWriter};
+#[cfg(feature = "std")]
+pub use scope::{ErrorScope, ScopeResultExt};
+#[cfg(feature = "std")]
+pub use search::{load_first_existing, FatSearchError};
+#[cfg(feature = "sentry")]
+pub use sentry::{sentry_event, with_sentry_report};
+#[cfg(feature = "signals")]
+pub use signal::{run_with_signals, Interrupted, INTERRUPTED_EXIT_CODE};
+#[cfg(feature = "std")]
+pub use sink::{
+    add_report_sink, clear_error_callbacks, clear_report_sinks, on_error, ErrorCallback,
+    ReportBuffer,
+};
+#[cfg(feature = "fs4")]
+pub use space::{check_space_for, fat_allocate, fat_available_space, FatSpaceError};
+#[cfg(feature = "futures")]
+pub use stream::FatStreamExt;
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub use tap::{ChainLevel, TapErrLog};
+#[cfg(feature = "std")]
+pub use template::{set_message_template, MessageTemplate};
+#[cfg(feature = "test-util")]
+pub use testing::{render_for_snapshot, ErrorChainMatcher, ErrorMatcher, FaultyDir};
+#[cfg(feature = "std")]
+pub use thread::{fat_spawn_thread, FatJoinHandle, FatThreadError};
+#[cfg(feature = "tracing")]
+pub use trace::{fat_io_wrap_std_traced, trace_error_chain};
+#[cfg(feature = "digest")]
+pub use verify::{fat_read_verified, ChecksumMismatch};
+
+/// the error followed by all of its causes
+pub(crate) fn chain<'a>(
+    error: &'a (dyn Error + 'static),
+) -> impl Iterator<Item = &'a (dyn Error + 'static)> {
+    core::iter::successors(Some(error), |&e| e.source())
+}
 
 /// # A wrapper for io::Error which also contains the file path it failed on
-/// This error comprises a `std::io::Error` as source and a `Pathbuf` containing the file path the operation failed on.
+/// This error comprises a `std::io::Error` as source and an `Arc<Path>` containing the file path the operation failed on.
+/// Callers which already hold the path in an `Arc<Path>` can create errors without copying it, see [`FatIOError::from_shared_path`].
 /// Consider using this together with [`fat_io_wrap_std`] for std::io functions.
 /// With the feature 'tokio' there's also: `fat_io_wrap_tokio` for tokio-async based functions.
-#[derive(Debug)]
+/// # Size guarantee
+/// `FatIOError` is exactly one pointer wide and has a niche, so `Result<(), FatIOError>` and `Option<FatIOError>` are one pointer wide as well.
+/// This is part of the API and checked at compile time, new information is always added behind the pointer.
+#[cfg(feature = "std")]
 pub struct FatIOError {
+    inner: Box<FatIOErrorInner>,
+}
+
+#[cfg(feature = "std")]
+struct FatIOErrorInner {
     source: std::io::Error,
-    file: PathBuf,
+    file: Arc<Path>,
+    correlation_id: Option<Box<str>>,
+    thread: Option<ThreadInfo>,
+    operation: Option<&'static str>,
+    call_site: Option<(&'static str, &'static Location<'static>)>,
+    duration: Option<Duration>,
+    metadata: Option<FileMetadata>,
 }
 
+#[cfg(feature = "std")]
 impl FatIOError {
     /// manually create a FatIOError from an std error when the file is still known
     pub fn from_std_io_err(e: std::io::Error, file: PathBuf) -> Self {
-        FatIOError { source: e, file }
+        Self::from_shared_path(e, file.into())
+    }
+
+    /// create a FatIOError sharing an already reference counted path, this does not copy the path
+    /// # Examples
+    /// ```rust
+    /// use std::io::ErrorKind;
+    /// use std::path::Path;
+    /// use std::sync::Arc;
+    /// use vg_errortools::FatIOError;
+    /// let path: Arc<Path> = Arc::from(Path::new("my_file.txt"));
+    /// let fat = FatIOError::from_shared_path(ErrorKind::NotFound.into(), path.clone());
+    /// assert!(Arc::ptr_eq(&fat.shared_path(), &path));
+    /// ```
+    pub fn from_shared_path(e: std::io::Error, file: Arc<Path>) -> Self {
+        FatIOError {
+            inner: Box::new(FatIOErrorInner {
+                source: e,
+                correlation_id: None,
+                thread: ThreadInfo::capture(),
+                operation: None,
+                call_site: None,
+                duration: None,
+                metadata: FileMetadata::capture(&file),
+                file,
+            }),
+        }
+    }
+
+    /// attach a correlation/request ID, it is shown in [`MainError`] reports and structured output
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::{FatIOError, MainError, MainErrorOptions};
+    /// let fat = FatIOError::from_std_io_err(std::io::Error::other("disk on fire"), "my_file.txt".into())
+    ///     .with_correlation_id("req-42");
+    /// assert_eq!(fat.correlation_id(), Some("req-42"));
+    /// let error: MainError = fat.into();
+    /// assert!(error.render(&MainErrorOptions::new()).ends_with("\ncorrelation id: req-42"));
+    /// ```
+    pub fn with_correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.inner.correlation_id = Some(id.into().into_boxed_str());
+        self
+    }
+
+    /// the correlation/request ID attached with [`FatIOError::with_correlation_id`]
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.inner.correlation_id.as_deref()
+    }
+
+    /// attach the path of the function which failed, like `std::fs::remove_file`, the message then names it, see [`fat_call!`]
+    pub fn with_operation(mut self, operation: &'static str) -> Self {
+        self.inner.operation = Some(operation);
+        self
+    }
+
+    /// the path of the function which failed, attached with [`FatIOError::with_operation`]
+    pub fn operation(&self) -> Option<&'static str> {
+        self.inner.operation
+    }
+
+    /// attach the source text of the path expression and the location of the call, see [`fat_io!`]
+    pub fn with_call_site(
+        mut self,
+        path_expression: &'static str,
+        location: &'static Location<'static>,
+    ) -> Self {
+        self.inner.call_site = Some((path_expression, location));
+        self
+    }
+
+    /// the source text of the path expression, attached with [`FatIOError::with_call_site`]
+    pub fn path_expression(&self) -> Option<&'static str> {
+        self.inner.call_site.map(|(expression, _)| expression)
+    }
+
+    /// the source location of the call, attached with [`FatIOError::with_call_site`]
+    pub fn location(&self) -> Option<&'static Location<'static>> {
+        self.inner.call_site.map(|(_, location)| location)
+    }
+
+    /// the thread this error was created on, if enabled by [`CaptureOptions::thread`]
+    pub fn thread(&self) -> Option<&ThreadInfo> {
+        self.inner.thread.as_ref()
+    }
+
+    /// the metadata of the file when this error was created, if enabled by [`CaptureOptions::metadata`] and the file existed
+    pub fn metadata(&self) -> Option<&FileMetadata> {
+        self.inner.metadata.as_ref()
+    }
+
+    /// attach how long the operation ran before it failed, it is shown in [`MainError`] reports from [`Verbosity::Detailed`] on
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.inner.duration = Some(duration);
+        self
+    }
+
+    /// # How long the operation ran before it failed
+    /// Measured by the wrappers if enabled by [`CaptureOptions::duration`], to tell instant failures from timeouts and hangs.
+    /// # Examples
+    /// ```rust
+    /// use std::fs::File;
+    /// use vg_errortools::{fat_io_wrap_std, set_capture_options, CaptureOptions, MainError, MainErrorOptions, Verbosity};
+    /// set_capture_options(CaptureOptions::new().duration(true));
+    /// let fat = fat_io_wrap_std("does/not/exist.txt", &File::open).unwrap_err();
+    /// assert!(fat.duration().is_some());
+    /// let verbose = MainErrorOptions::new().verbosity(Verbosity::Detailed);
+    /// assert!(MainError::from(fat).render(&verbose).contains("\nduration: "));
+    /// ```
+    pub fn duration(&self) -> Option<Duration> {
+        self.inner.duration
+    }
+
+    /// attach the time passed since `started`, if the duration is captured
+    pub(crate) fn with_elapsed(self, started: Option<Instant>) -> Self {
+        match started {
+            Some(started) => self.with_duration(started.elapsed()),
+            None => self,
+        }
+    }
+
+    /// the kind of the wrapped `std::io::Error`
+    pub fn kind(&self) -> std::io::ErrorKind {
+        self.inner.source.kind()
+    }
+
+    /// # The kind and the path, to write recovery logic as a single `match`
+    /// # Examples
+    /// ```rust
+    /// use std::io::ErrorKind;
+    /// use vg_errortools::FatIOError;
+    /// let fat = FatIOError::from_std_io_err(ErrorKind::NotFound.into(), "cache/mesh.bin".into());
+    /// let recovery = match fat.classify() {
+    ///     (ErrorKind::NotFound, path) if path.starts_with("cache") => "rebuild the cache",
+    ///     (ErrorKind::PermissionDenied, _) => "ask for elevation",
+    ///     _ => "give up",
+    /// };
+    /// assert_eq!(recovery, "rebuild the cache");
+    /// ```
+    pub fn classify(&self) -> (std::io::ErrorKind, &Path) {
+        (self.kind(), self.path())
+    }
+
+    /// the wrapped `std::io::Error`
+    pub fn io_error(&self) -> &std::io::Error {
+        &self.inner.source
+    }
+
+    /// the path of the file the operation failed on
+    pub fn path(&self) -> &Path {
+        &self.inner.file
+    }
+
+    /// the path of the file the operation failed on as a cheap to clone shared handle
+    pub fn shared_path(&self) -> Arc<Path> {
+        self.inner.file.clone()
+    }
+
+    /// replace the wrapped `std::io::Error`, keeping the path and all attached information
+    /// # Examples
+    /// ```rust
+    /// use std::io::ErrorKind;
+    /// use vg_errortools::FatIOError;
+    /// let fat = FatIOError::from_std_io_err(ErrorKind::Other.into(), "my_file.txt".into())
+    ///     .with_correlation_id("req-42")
+    ///     .map_source(|e| std::io::Error::new(ErrorKind::TimedOut, e));
+    /// assert_eq!(fat.kind(), ErrorKind::TimedOut);
+    /// assert_eq!(fat.correlation_id(), Some("req-42"));
+    /// ```
+    pub fn map_source(mut self, f: impl FnOnce(std::io::Error) -> std::io::Error) -> Self {
+        let placeholder = std::io::Error::from(std::io::ErrorKind::Other);
+        let source = std::mem::replace(&mut self.inner.source, placeholder);
+        self.inner.source = f(source);
+        self
+    }
+
+    /// replace the path, e.g. to strip a sandbox prefix, keeping the error and all attached information
+    /// # Examples
+    /// ```rust
+    /// use std::path::Path;
+    /// use vg_errortools::FatIOError;
+    /// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "/sandbox/7/data/mesh.stl".into())
+    ///     .map_path(|path| Path::new("/").join(path.strip_prefix("/sandbox/7").unwrap_or(path)));
+    /// assert_eq!(fat.path(), Path::new("/data/mesh.stl"));
+    /// ```
+    pub fn map_path(mut self, f: impl FnOnce(&Path) -> PathBuf) -> Self {
+        self.inner.file = Arc::from(f(&self.inner.file));
+        self
+    }
+
+    /// replace the path, e.g. a temporary file by the destination it was meant for, keeping the error and all attached information
+    /// # Examples
+    /// ```rust
+    /// use std::path::Path;
+    /// use vg_errortools::{fat_io_wrap_std, FatIOError};
+    /// let temporary = std::env::temp_dir().join("does/not/exist/export.tmp");
+    /// let error = fat_io_wrap_std(&temporary, &std::fs::File::open)
+    ///     .map_err(|e| e.with_path("exports/scene.vgl"))
+    ///     .unwrap_err();
+    /// assert_eq!(error.path(), Path::new("exports/scene.vgl"));
+    /// ```
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.inner.file = Arc::from(path.as_ref());
+        self
+    }
+
+    /// # Take the wrapped `std::io::Error` and the path out of the error
+    /// Other attached information is dropped. The path is copied out of its shared handle, [`FatIOError::into_shared_parts`] avoids that.
+    /// # Examples
+    /// ```rust
+    /// use std::io::ErrorKind;
+    /// use std::path::Path;
+    /// use vg_errortools::FatIOError;
+    /// let fat = FatIOError::from_std_io_err(ErrorKind::NotFound.into(), "my_file.txt".into());
+    /// let (error, path) = fat.into_parts();
+    /// assert_eq!((error.kind(), path.as_path()), (ErrorKind::NotFound, Path::new("my_file.txt")));
+    /// ```
+    pub fn into_parts(self) -> (std::io::Error, PathBuf) {
+        let (source, file) = self.into_shared_parts();
+        (source, file.to_path_buf())
+    }
+
+    /// take the wrapped `std::io::Error` and the shared path out of the error without copying, see [`FatIOError::into_parts`]
+    pub fn into_shared_parts(self) -> (std::io::Error, Arc<Path>) {
+        let inner = *self.inner;
+        (inner.source, inner.file)
+    }
+
+    /// recover a FatIOError that was packed into a `std::io::Error` as custom payload, e.g. by the `From` conversion
+    /// # Examples
+    /// ```rust
+    /// use std::io::ErrorKind;
+    /// use std::path::Path;
+    /// use vg_errortools::FatIOError;
+    /// let fat = FatIOError::from_std_io_err(ErrorKind::NotFound.into(), "my_file.txt".into());
+    /// let io_error: std::io::Error = fat.into();
+    /// let recovered = FatIOError::from_io_error_payload(&io_error).unwrap();
+    /// assert_eq!(recovered.path(), Path::new("my_file.txt"));
+    /// ```
+    pub fn from_io_error_payload(e: &std::io::Error) -> Option<&FatIOError> {
+        e.get_ref()?.downcast_ref::<FatIOError>()
+    }
+
+    /// pack `e` with `file` into an io error of the same kind, errors which already carry a FatIOError are kept as they are
+    pub(crate) fn embed(e: std::io::Error, file: &Arc<Path>) -> std::io::Error {
+        if Self::from_io_error_payload(&e).is_some() {
+            return e;
+        }
+        Self::from_shared_path(e, file.clone()).into()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Debug for FatIOError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FatIOError")
+            .field("source", &self.inner.source)
+            .field("file", &self.inner.file)
+            .field("correlation_id", &self.inner.correlation_id)
+            .field("thread", &self.inner.thread)
+            .field("operation", &self.inner.operation)
+            .field("call_site", &self.inner.call_site)
+            .field("duration", &self.inner.duration)
+            .field("metadata", &self.inner.metadata)
+            .finish()
     }
 }
 
+#[cfg(feature = "std")]
 impl Display for FatIOError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Operating on file '{}' failed with error {}",
-            self.file.to_string_lossy(),
-            self.source
-        )?;
-        Ok(())
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        template::fat_io_error(self, f)
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for FatIOError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        Some(&self.source)
+        Some(&self.inner.source)
+    }
+}
+
+/// Converts the fat error back into a `std::io::Error` for APIs which can only return those, e.g. `Read`/`Write` impls.
+/// The fat error is kept as custom payload so the path is not lost and the kind of the wrapped error is preserved.
+/// # Examples
+/// ```rust
+/// use std::io::ErrorKind;
+/// use vg_errortools::FatIOError;
+/// let fat = FatIOError::from_std_io_err(ErrorKind::NotFound.into(), "my_file.txt".into());
+/// let io_error: std::io::Error = fat.into();
+/// assert_eq!(io_error.kind(), ErrorKind::NotFound);
+/// assert!(io_error.to_string().contains("my_file.txt"));
+/// ```
+#[cfg(feature = "std")]
+impl From<FatIOError> for std::io::Error {
+    fn from(e: FatIOError) -> Self {
+        std::io::Error::new(e.kind(), e)
     }
 }
 
 /// # Wrapper for std::io functions
 /// This runs any std::io function which only takes a single argument `impl AsRef<Path>` and wraps the filename argument in a [`FatIOError`] if one occurs.
-/// Since this operation involves a Pathbuf-Deepcopy it's not free, so be careful in high frequency contexts.
+/// The path is only borrowed while the function runs, the deep copy of the path is made in the error case only, so the success path stays allocation-free.
 /// # Examples
 /// ```rust, no_run
 /// use std::fs::File;
@@ -59,50 +615,64 @@ impl Error for FatIOError {
 ///
 /// ```rust, no_run
 /// use std::fs::read_to_string;
+/// use std::path::PathBuf;
 /// use vg_errortools::fat_io_wrap_std;
-/// let to_string_result = fat_io_wrap_std("my_file.txt", &read_to_string);
+/// let path = PathBuf::from("my_file.txt");
+/// let to_string_result = fat_io_wrap_std(&path, &read_to_string);
 /// ```
 ///
-pub fn fat_io_wrap_std<T, P: AsRef<Path>>(
-    path: P,
-    f: &dyn Fn(P) -> std::io::Result<T>,
+/// ```rust
+/// use std::fs::File;
+/// use std::path::Path;
+/// use vg_errortools::fat_io_wrap_std;
+/// let error = fat_io_wrap_std("does/not/exist.txt", &File::open).unwrap_err();
+/// assert_eq!(error.path(), Path::new("does/not/exist.txt"));
+/// ```
+///
+#[cfg(feature = "std")]
+pub fn fat_io_wrap_std<'a, T, P: AsRef<Path> + ?Sized>(
+    path: &'a P,
+    f: &dyn Fn(&'a P) -> std::io::Result<T>,
 ) -> Result<T, FatIOError> {
-    let path_buf = path.as_ref().to_path_buf();
-    let result = f(path);
-    result.map_err(|e| FatIOError {
-        source: e,
-        file: path_buf,
+    let started = capture::start_timer();
+    f(path).map_err(|e| {
+        FatIOError::from_shared_path(e, canonical::recorded_path(path.as_ref()))
+            .with_elapsed(started)
     })
 }
 
 /// # Wrapper for tokio::fs functions
 /// This runs any tokio::fs function which only takes a single argument `impl AsRef<Path>` and wraps the filename argument in a [`FatIOError`] if one occurs.
-/// Since this operation involves a Pathbuf-Deepcopy it's not free, so be careful in high frequency contexts.
+/// Like [`fat_io_wrap_std`] the path is only borrowed and copied in the error case only.
 /// # Examples
 /// ```rust, no_run
-/// use errortools::{fat_io_wrap_tokio};
-/// async fn some_fn() -> Result<tokio::fs::File, errortools::FatIOError> {
+/// use vg_errortools::{fat_io_wrap_tokio};
+/// async fn some_fn() -> Result<tokio::fs::File, vg_errortools::FatIOError> {
 ///   fat_io_wrap_tokio("my_file.txt", tokio::fs::File::open).await
 /// }
 /// ```
 ///
 /// ```rust, no_run
-/// use errortools::{fat_io_wrap_tokio};
-/// async fn some_fn() -> Result<String, errortools::FatIOError> {
+/// use vg_errortools::{fat_io_wrap_tokio};
+/// async fn some_fn() -> Result<String, vg_errortools::FatIOError> {
 ///   fat_io_wrap_tokio("my_file.txt", tokio::fs::read_to_string).await
 /// }
 /// ```
 ///
 #[cfg(feature = "tokio")]
-pub async fn fat_io_wrap_tokio<T, P: AsRef<Path>, F: Future<Output = std::io::Result<T>>>(
-    path: P,
-    f: fn(P) -> F,
+pub async fn fat_io_wrap_tokio<
+    'a,
+    T,
+    P: AsRef<Path> + ?Sized,
+    F: Future<Output = std::io::Result<T>>,
+>(
+    path: &'a P,
+    f: fn(&'a P) -> F,
 ) -> Result<T, FatIOError> {
-    let path_buf = path.as_ref().to_path_buf();
-    let result = f(path).await;
-    result.map_err(|e| FatIOError {
-        source: e,
-        file: path_buf,
+    let started = capture::start_timer();
+    f(path).await.map_err(|e| {
+        FatIOError::from_shared_path(e, canonical::recorded_path(path.as_ref()))
+            .with_elapsed(started)
     })
 }
 
@@ -121,22 +691,340 @@ pub async fn fat_io_wrap_tokio<T, P: AsRef<Path>, F: Future<Output = std::io::Re
 ///     Ok(())
 /// }
 /// ```
-pub struct MainError(Box<dyn Error>);
+/// # Report style
+/// The `Debug` output follows the process wide [`set_reporter`] and [`set_error_style`] selection.
+/// Libraries and binaries can fix the style at the type level instead, e.g. `MainError<JsonReport>`, see [`ReportStyle`].
+/// # Size guarantee
+/// Like [`FatIOError`], `MainError` is exactly one pointer wide with a niche, `Result<(), MainError>` included.
+#[cfg(feature = "std")]
+pub struct MainError<R = ConfiguredReport> {
+    inner: Box<MainErrorInner>,
+    style: PhantomData<fn() -> R>,
+}
+
+#[cfg(feature = "std")]
+struct MainErrorInner {
+    error: Box<dyn Error>,
+    backtrace: Backtrace,
+    correlation_id: Option<String>,
+    timestamp: Option<SystemTime>,
+    thread: Option<ThreadInfo>,
+    environment: Option<Environment>,
+    scopes: Vec<Cow<'static, str>>,
+    returned_from: &'static Location<'static>,
+    crash_report: OnceLock<Result<PathBuf, String>>,
+    notified: AtomicBool,
+}
+
+#[cfg(feature = "std")]
+impl MainError {
+    /// # Wrap `error`, the same as `From`
+    /// Lets `MainError::from(error)` pick the default [`ConfiguredReport`] style without type annotations.
+    #[track_caller]
+    pub fn from<E: Into<Box<dyn Error>>>(error: E) -> Self {
+        MainError::with_backtrace(error.into(), capture_backtrace())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> MainError<R> {
+    /// # The same error with another [`ReportStyle`]
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::{JsonReport, MainError};
+    /// let error = MainError::from(std::io::Error::other("disk on fire")).with_style::<JsonReport>();
+    /// assert_eq!(format!("{error:?}"), r#"{"message":"disk on fire","causes":[]}"#);
+    /// ```
+    pub fn with_style<S>(self) -> MainError<S> {
+        MainError {
+            inner: self.inner,
+            style: PhantomData,
+        }
+    }
+
+    /// the wrapped error
+    pub fn error(&self) -> &(dyn Error + 'static) {
+        self.inner.error.as_ref()
+    }
+
+    /// the backtrace captured on creation, see [`std::backtrace::Backtrace::capture`]
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.inner.backtrace
+    }
+
+    /// attach a correlation/request ID, it is shown in the report and structured output
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::{MainError, MainErrorOptions, Style};
+    /// let error = MainError::from(std::io::Error::other("disk on fire")).with_correlation_id("req-42");
+    /// assert_eq!(
+    ///     error.render(&MainErrorOptions::new().style(Style::Json)),
+    ///     r#"{"message":"disk on fire","causes":[],"correlation_id":"req-42"}"#
+    /// );
+    /// ```
+    pub fn with_correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.inner.correlation_id = Some(id.into());
+        self
+    }
+
+    /// the correlation/request ID attached to this error, or else to the first [`FatIOError`] in the chain which has one
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.inner.correlation_id.as_deref().or_else(|| {
+            chain(self.error())
+                .filter_map(|e| e.downcast_ref::<FatIOError>())
+                .find_map(FatIOError::correlation_id)
+        })
+    }
+
+    /// the time this error was created, if enabled by [`CaptureOptions::timestamp`]
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        self.inner.timestamp
+    }
+
+    /// the thread the first [`FatIOError`] in the chain was created on, or else this error, if enabled by [`CaptureOptions::thread`]
+    pub fn thread(&self) -> Option<&ThreadInfo> {
+        chain(self.error())
+            .filter_map(|e| e.downcast_ref::<FatIOError>())
+            .find_map(FatIOError::thread)
+            .or(self.inner.thread.as_ref())
+    }
+
+    /// the working directory, user and host this error was created in, if enabled by [`CaptureOptions::environment`]
+    pub fn environment(&self) -> Option<&Environment> {
+        self.inner.environment.as_ref()
+    }
+
+    /// # Where the error was converted into a `MainError`, usually the `?` it crossed into `main` with
+    /// Reports show it from [`Verbosity::Detailed`] on, which narrows the search when the chain itself is generic.
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::{MainError, MainErrorOptions, Verbosity};
+    /// fn export() -> Result<(), MainError> {
+    ///     std::fs::read("does/not/exist.raw")?;
+    ///     Ok(())
+    /// }
+    /// let (error, line) = (export().unwrap_err(), line!() - 3);
+    /// assert_eq!(error.returned_from().line(), line);
+    /// let report = error.render(&MainErrorOptions::new().verbosity(Verbosity::Detailed));
+    /// assert!(report.contains(&format!("\nerror returned from: {}:{line}", file!())));
+    /// ```
+    pub fn returned_from(&self) -> &'static Location<'static> {
+        self.inner.returned_from
+    }
+
+    /// the labels of the [`ErrorScope`]s active when this error was created, outermost first
+    pub fn scopes(&self) -> &[Cow<'static, str>] {
+        &self.inner.scopes
+    }
 
-impl<E: Into<Box<dyn Error>>> From<E> for MainError {
+    pub(crate) fn to_report(&self) -> Report<'_> {
+        let mut details = code::details(self.error());
+        #[cfg(feature = "diagnose")]
+        details.extend(diagnose::details(self.error()));
+        if let Some(timestamp) = self.timestamp() {
+            details.push(Detail {
+                key: "timestamp",
+                label: "time",
+                value: capture::format_timestamp(timestamp),
+                verbosity: Verbosity::Normal,
+            });
+        }
+        if let Some(thread) = self.thread() {
+            details.push(Detail {
+                key: "thread",
+                label: "thread",
+                value: thread.to_string(),
+                verbosity: Verbosity::Detailed,
+            });
+        }
+        let returned_from = self.returned_from();
+        details.push(Detail {
+            key: "returned_from",
+            label: "error returned from",
+            value: format!("{}:{}", returned_from.file(), returned_from.line()),
+            verbosity: Verbosity::Detailed,
+        });
+        if let Some((expression, location)) = chain(self.error())
+            .filter_map(|e| e.downcast_ref::<FatIOError>())
+            .find_map(|fat| fat.inner.call_site)
+        {
+            details.push(Detail {
+                key: "location",
+                label: "at",
+                value: location.to_string(),
+                verbosity: Verbosity::Detailed,
+            });
+            details.push(Detail {
+                key: "path_expression",
+                label: "path expression",
+                value: expression.to_string(),
+                verbosity: Verbosity::Detailed,
+            });
+        }
+        if let Some(duration) = chain(self.error())
+            .filter_map(|e| e.downcast_ref::<FatIOError>())
+            .find_map(FatIOError::duration)
+        {
+            details.push(Detail {
+                key: "duration",
+                label: "duration",
+                value: format!("{duration:?}"),
+                verbosity: Verbosity::Detailed,
+            });
+        }
+        if let Some(metadata) = chain(self.error())
+            .filter_map(|e| e.downcast_ref::<FatIOError>())
+            .find_map(FatIOError::metadata)
+        {
+            details.push(Detail {
+                key: "file_metadata",
+                label: "file",
+                value: metadata.to_string(),
+                verbosity: Verbosity::Detailed,
+            });
+        }
+        if let Some(environment) = self.environment() {
+            details.push(Detail {
+                key: "context",
+                label: "context",
+                value: environment.to_string(),
+                verbosity: Verbosity::Normal,
+            });
+        }
+        if !self.scopes().is_empty() {
+            details.push(Detail {
+                key: "scopes",
+                label: "while",
+                value: self.scopes().join(" > "),
+                verbosity: Verbosity::Normal,
+            });
+        }
+        if let Some(id) = self.correlation_id() {
+            details.push(Detail {
+                key: "correlation_id",
+                label: "correlation id",
+                value: id.to_string(),
+                verbosity: Verbosity::Normal,
+            });
+        }
+        if let Some(system) = footer::system_info() {
+            details.push(Detail {
+                key: "system",
+                label: "system",
+                value: system,
+                verbosity: Verbosity::Normal,
+            });
+        }
+        Report {
+            error: self.error(),
+            backtrace: Some(&self.inner.backtrace),
+            details,
+        }
+    }
+
+    /// render the report as configured by `options`
+    pub fn render(&self, options: &MainErrorOptions) -> String {
+        options.render_to_string(&self.to_report())
+    }
+
+    /// write the report as configured by `options` to the configured output stream, nothing for broken pipes if [`set_broken_pipe_exit_code`] is set
+    pub fn report(&self, options: &MainErrorOptions) -> std::io::Result<()> {
+        if self.broken_pipe_exit().is_some() {
+            return Ok(());
+        }
+        self.notify_once();
+        options.report(&self.to_report())
+    }
+}
+
+/// capture a backtrace if enabled by `RUST_BACKTRACE` or [`Verbosity::Full`]
+#[cfg(feature = "std")]
+fn capture_backtrace() -> Backtrace {
+    match Verbosity::from_env() {
+        Some(Verbosity::Full) => Backtrace::force_capture(),
+        _ => Backtrace::capture(),
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> MainError<R> {
+    #[track_caller]
+    fn with_backtrace(error: Box<dyn Error>, backtrace: Backtrace) -> Self {
+        MainError::recorded(
+            error,
+            backtrace,
+            ThreadInfo::capture(),
+            scope::current(),
+            Location::caller(),
+        )
+    }
+
+    /// wrap `error` with the backtrace, thread and scopes recorded where it occurred, which may be another thread
+    fn recorded(
+        error: Box<dyn Error>,
+        backtrace: Backtrace,
+        thread: Option<ThreadInfo>,
+        scopes: Vec<Cow<'static, str>>,
+        returned_from: &'static Location<'static>,
+    ) -> Self {
+        MainError {
+            inner: Box::new(MainErrorInner {
+                error,
+                backtrace,
+                correlation_id: None,
+                timestamp: capture_options().captures_timestamp().then(SystemTime::now),
+                thread,
+                environment: Environment::capture(),
+                scopes,
+                returned_from,
+                crash_report: OnceLock::new(),
+                notified: AtomicBool::new(false),
+            }),
+            style: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R, E: Into<Box<dyn Error>>> From<E> for MainError<R> {
+    #[track_caller]
     fn from(e: E) -> Self {
-        MainError(e.into())
+        MainError::with_backtrace(e.into(), capture_backtrace())
+    }
+}
+
+/// the message of the error, without causes or report details
+#[cfg(feature = "std")]
+impl<R> Display for MainError<R> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.inner.error, f)
     }
 }
 
-impl Debug for MainError {
+#[cfg(feature = "std")]
+impl<R: ReportStyle> Debug for MainError<R> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        Display::fmt(&self.0, f)?;
-        let mut source = self.0.source();
-        while let Some(error) = source {
-            write!(f, "\ncaused by: {}", error)?;
-            source = error.source();
+        let mut text = String::new();
+        let _ = R::render(self, &mut text);
+        if let Some(note) = self.crash_report_note() {
+            text.push('\n');
+            text.push_str(&note);
         }
-        Ok(())
+        if let Some(footer) = report_footer() {
+            text.push_str("\n\n");
+            text.push_str(&footer);
+        }
+        f.write_str(&text)
     }
 }
+
+#[cfg(feature = "std")]
+const _: () = assert!(size_of::<FatIOError>() == size_of::<usize>());
+#[cfg(feature = "std")]
+const _: () = assert!(size_of::<Result<(), FatIOError>>() == size_of::<usize>());
+#[cfg(feature = "std")]
+const _: () = assert!(size_of::<Option<FatIOError>>() == size_of::<usize>());
+#[cfg(feature = "std")]
+const _: () = assert!(size_of::<MainError>() == size_of::<usize>());
+#[cfg(feature = "std")]
+const _: () = assert!(size_of::<Result<(), MainError>>() == size_of::<usize>());
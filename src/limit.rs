@@ -0,0 +1,112 @@
+use crate::{canonical, FatIOError};
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+
+/// a byte count in binary units, e.g. `2.3 GiB` or `256 MiB`
+pub(crate) struct HumanSize(pub(crate) u64);
+
+impl Display for HumanSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit + 1 < UNITS.len() {
+            value /= 1024.0;
+            unit += 1;
+        }
+        let rounded = (value * 10.0).round() / 10.0;
+        if rounded.fract() == 0.0 {
+            write!(f, "{rounded:.0} {}", UNITS[unit])
+        } else {
+            write!(f, "{rounded:.1} {}", UNITS[unit])
+        }
+    }
+}
+
+/// # A file exceeding the size limit of [`fat_read_limited`]
+/// The io error of kind `FileTooLarge` returned by [`fat_read_limited`] wraps it, inside a [`FatIOError`] for the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeLimitExceeded {
+    size: Option<u64>,
+    limit: u64,
+}
+
+impl SizeLimitExceeded {
+    /// the size of the file, `None` if it grew past the limit while reading
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// the limit in bytes
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+}
+
+impl Display for SizeLimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.size {
+            Some(size) => write!(
+                f,
+                "file is {}, limit is {}",
+                HumanSize(size),
+                HumanSize(self.limit)
+            ),
+            None => write!(
+                f,
+                "file is larger than the limit of {}",
+                HumanSize(self.limit)
+            ),
+        }
+    }
+}
+
+impl Error for SizeLimitExceeded {}
+
+/// # Read a whole file, refusing files larger than `max_bytes`
+/// The size is checked before reading and never more than `max_bytes` are allocated, also if the file grows meanwhile.
+/// Oversized files fail with a [`FatIOError`] of kind `FileTooLarge` wrapping a [`SizeLimitExceeded`].
+/// # Examples
+/// ```rust
+/// use vg_errortools::{fat_read_limited, SizeLimitExceeded};
+/// let path = std::env::temp_dir().join("vg_errortools_limited_doc.bin");
+/// std::fs::write(&path, vec![0u8; 3 * 1024 * 1024]).unwrap();
+/// assert_eq!(fat_read_limited(&path, 4 * 1024 * 1024).unwrap().len(), 3 * 1024 * 1024);
+/// let error = fat_read_limited(&path, 256 * 1024).unwrap_err();
+/// assert_eq!(error.kind(), std::io::ErrorKind::FileTooLarge);
+/// assert!(error.to_string().ends_with("file is 3 MiB, limit is 256 KiB"));
+/// let exceeded = error.io_error().get_ref().unwrap().downcast_ref::<SizeLimitExceeded>().unwrap();
+/// assert_eq!(exceeded.size(), Some(3 * 1024 * 1024));
+/// ```
+pub fn fat_read_limited(path: impl AsRef<Path>, max_bytes: u64) -> Result<Vec<u8>, FatIOError> {
+    let path = path.as_ref();
+    let fat = |e| FatIOError::from_shared_path(e, canonical::recorded_path(path));
+    let exceeded = |size| {
+        fat(std::io::Error::new(
+            ErrorKind::FileTooLarge,
+            SizeLimitExceeded {
+                size,
+                limit: max_bytes,
+            },
+        ))
+    };
+    let file = std::fs::File::open(path).map_err(fat)?;
+    let size = file.metadata().map_err(fat)?.len();
+    if size > max_bytes {
+        return Err(exceeded(Some(size)));
+    }
+    let mut content = Vec::with_capacity(size as usize);
+    let mut file = file.take(max_bytes);
+    file.read_to_end(&mut content).map_err(fat)?;
+    let mut file = file.into_inner();
+    if file.read(&mut [0u8]).map_err(fat)? > 0 {
+        return Err(exceeded(None));
+    }
+    Ok(content)
+}
@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// # A poisoned lock with its name
+/// A lock is poisoned if a thread panicked while holding it, the name tells which of them it was.
+/// The guard of the poisoned lock is released, so the error can travel up the chain. Create it with [`fat_lock`], [`fat_read_lock`] or [`fat_write_lock`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FatLockError {
+    name: &'static str,
+}
+
+impl FatLockError {
+    /// the name of the lock
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl Display for FatLockError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "lock '{}' is poisoned, another thread panicked while holding it",
+            self.name
+        )
+    }
+}
+
+impl Error for FatLockError {}
+
+/// # Lock a mutex, naming it if it is poisoned
+/// # Examples
+/// ```rust
+/// use std::sync::Mutex;
+/// use vg_errortools::fat_lock;
+/// let cache_index = Mutex::new(0);
+/// let _ = std::thread::scope(|s| {
+///     s.spawn(|| {
+///         let _guard = cache_index.lock().unwrap();
+///         panic!("indexing failed");
+///     })
+///     .join()
+/// });
+/// let error = fat_lock(&cache_index, "cache index").unwrap_err();
+/// assert_eq!(error.to_string(), "lock 'cache index' is poisoned, another thread panicked while holding it");
+/// ```
+pub fn fat_lock<'a, T: ?Sized>(
+    mutex: &'a Mutex<T>,
+    name: &'static str,
+) -> Result<MutexGuard<'a, T>, FatLockError> {
+    mutex.lock().map_err(|_| FatLockError { name })
+}
+
+/// lock a `RwLock` for reading, naming it if it is poisoned
+pub fn fat_read_lock<'a, T: ?Sized>(
+    lock: &'a RwLock<T>,
+    name: &'static str,
+) -> Result<RwLockReadGuard<'a, T>, FatLockError> {
+    lock.read().map_err(|_| FatLockError { name })
+}
+
+/// lock a `RwLock` for writing, naming it if it is poisoned
+pub fn fat_write_lock<'a, T: ?Sized>(
+    lock: &'a RwLock<T>,
+    name: &'static str,
+) -> Result<RwLockWriteGuard<'a, T>, FatLockError> {
+    lock.write().map_err(|_| FatLockError { name })
+}
@@ -0,0 +1,28 @@
+use std::error::Error;
+
+/// # Log an error with its cause chain
+/// The error itself is logged at `level`, each cause as a separate record at debug level, so the chain keeps its structure in normal log output.
+/// Prefer the [`log_error_chain!`](macro@crate::log_error_chain) macro, which uses the calling module as target.
+pub fn log_error_chain(target: &str, level: log::Level, error: &dyn Error) {
+    log::log!(target: target, level, "{error}");
+    let mut source = error.source();
+    while let Some(cause) = source {
+        log::debug!(target: target, "caused by: {cause}");
+        source = cause.source();
+    }
+}
+
+/// # Log an error and its cause chain from the calling module
+/// Expands to [`log_error_chain`](fn@crate::log_error_chain) with `module_path!()` as target.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{log_error_chain, FatIOError};
+/// let error = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "my_file.txt".into());
+/// log_error_chain!(log::Level::Error, error);
+/// ```
+#[macro_export]
+macro_rules! log_error_chain {
+    ($level:expr, $err:expr) => {
+        $crate::log_error_chain(module_path!(), $level, &$err)
+    };
+}
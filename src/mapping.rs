@@ -0,0 +1,142 @@
+use crate::canonical;
+use crate::template::display_path;
+use memmap2::{Mmap, MmapOptions};
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// # A failed memory map with the path, the requested range and the file length
+/// Created by [`fat_map`] and [`fat_map_range`], the source is the io error.
+#[derive(Debug)]
+pub struct FatMapError {
+    path: PathBuf,
+    offset: u64,
+    len: Option<usize>,
+    file_len: Option<u64>,
+    source: std::io::Error,
+}
+
+impl FatMapError {
+    /// the mapped path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// the requested offset into the file
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// the requested length, `None` for the rest of the file
+    pub fn requested_len(&self) -> Option<usize> {
+        self.len
+    }
+
+    /// the length of the file, if it could be queried
+    pub fn file_len(&self) -> Option<u64> {
+        self.file_len
+    }
+
+    /// whether the file is shorter than the requested range
+    pub fn is_truncated(&self) -> bool {
+        match (self.file_len, self.len) {
+            (Some(file_len), Some(len)) => file_len < self.offset.saturating_add(len as u64),
+            (Some(file_len), None) => file_len < self.offset,
+            (None, _) => false,
+        }
+    }
+
+    /// the kind of the io error
+    pub fn kind(&self) -> ErrorKind {
+        self.source.kind()
+    }
+}
+
+impl Display for FatMapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "mapping '{}' ", display_path(&self.path))?;
+        match self.len {
+            Some(len) => write!(f, "({len} bytes at offset {})", self.offset)?,
+            None => write!(f, "(from offset {})", self.offset)?,
+        }
+        if self.is_truncated() {
+            if let Some(file_len) = self.file_len {
+                write!(f, " failed, the file is only {file_len} bytes long")?;
+                return Ok(());
+            }
+        }
+        write!(f, " failed with error {}", self.source)
+    }
+}
+
+impl Error for FatMapError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// keeps the kind
+impl From<FatMapError> for std::io::Error {
+    fn from(e: FatMapError) -> Self {
+        std::io::Error::new(e.kind(), e)
+    }
+}
+
+/// # Map a whole file read-only
+/// See [`fat_map_range`].
+/// # Safety
+/// As for [`Mmap::map`]: the file must not be modified or truncated while the map is alive, by this or any other process.
+pub unsafe fn fat_map(path: impl AsRef<Path>) -> Result<Mmap, FatMapError> {
+    fat_map_range(path, 0, None)
+}
+
+/// # Map `len` bytes starting at `offset` of a file read-only, `None` maps the rest of the file
+/// Ranges beyond the end of the file are rejected with an `UnexpectedEof` error before mapping, the error reports the requested range and the actual file length.
+/// # Safety
+/// As for [`Mmap::map`]: the file must not be modified or truncated while the map is alive, by this or any other process.
+/// # Examples
+/// ```rust
+/// use vg_errortools::fat_map_range;
+/// let path = std::env::temp_dir().join("vg_errortools_map_doc.raw");
+/// std::fs::write(&path, [1u8, 2, 3, 4]).unwrap();
+/// let map = unsafe { fat_map_range(&path, 2, Some(2)) }.unwrap();
+/// assert_eq!(&map[..], [3, 4]);
+/// let error = unsafe { fat_map_range(&path, 0, Some(4096)) }.unwrap_err();
+/// assert!(error.is_truncated());
+/// assert!(error.to_string().ends_with("(4096 bytes at offset 0) failed, the file is only 4 bytes long"));
+/// ```
+pub unsafe fn fat_map_range(
+    path: impl AsRef<Path>,
+    offset: u64,
+    len: Option<usize>,
+) -> Result<Mmap, FatMapError> {
+    let path = path.as_ref();
+    let error = |file_len, source| FatMapError {
+        path: canonical::recorded_path(path).to_path_buf(),
+        offset,
+        len,
+        file_len,
+        source,
+    };
+    let file = File::open(path).map_err(|e| error(None, e))?;
+    let file_len = file.metadata().ok().map(|metadata| metadata.len());
+    let truncated = error(
+        file_len,
+        std::io::Error::new(
+            ErrorKind::UnexpectedEof,
+            "the file is shorter than the requested range",
+        ),
+    );
+    if truncated.is_truncated() {
+        return Err(truncated);
+    }
+    let mut options = MmapOptions::new();
+    options.offset(offset);
+    if let Some(len) = len {
+        options.len(len);
+    }
+    options.map(&file).map_err(|e| error(file_len, e))
+}
@@ -0,0 +1,35 @@
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+static NFC_PATHS: AtomicBool = AtomicBool::new(false);
+
+/// # NFC-normalize paths in error messages
+/// File systems like APFS hand out decomposed (NFD) names, so visually identical paths can differ in messages and in comparisons of messages.
+/// Once enabled, the paths in [`crate::FatIOError`] messages are composed (NFC) before display. Disabled by default, call this once at startup.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_nfc_paths, FatIOError};
+/// let decomposed = "Mu\u{308}ller.txt";
+/// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), decomposed.into());
+/// set_nfc_paths(true);
+/// assert!(fat.to_string().contains("'M\u{fc}ller.txt'"));
+/// set_nfc_paths(false);
+/// assert!(fat.to_string().contains(decomposed));
+/// ```
+pub fn set_nfc_paths(enabled: bool) {
+    NFC_PATHS.store(enabled, Ordering::Relaxed);
+}
+
+/// true if paths are normalized, see [`set_nfc_paths`]
+pub fn nfc_paths() -> bool {
+    NFC_PATHS.load(Ordering::Relaxed)
+}
+
+/// compose `text` if enabled by [`set_nfc_paths`]
+pub(crate) fn normalize(text: Cow<'_, str>) -> Cow<'_, str> {
+    if !nfc_paths() || is_nfc_quick(text.chars()) == IsNormalized::Yes {
+        return text;
+    }
+    text.nfc().collect::<String>().into()
+}
@@ -0,0 +1,81 @@
+use crate::{chain, FatIOError, MainError};
+use opentelemetry::trace::Span;
+use opentelemetry::{Array, KeyValue, StringValue, Value};
+use std::backtrace::BacktraceStatus;
+use std::error::Error;
+
+fn type_name(error: &(dyn Error + 'static)) -> &'static str {
+    if error.is::<FatIOError>() {
+        std::any::type_name::<FatIOError>()
+    } else if error.is::<std::io::Error>() {
+        std::any::type_name::<std::io::Error>()
+    } else {
+        "dyn std::error::Error"
+    }
+}
+
+/// # OpenTelemetry attributes describing an error chain
+/// Follows the semantic conventions for exceptions: `exception.type` and `exception.message` describe the error itself,
+/// `exception.causes` lists the messages of all causes and `file.path` holds the path of the first [`FatIOError`] in the chain.
+/// A correlation ID of a [`FatIOError`] in the chain is recorded as `correlation.id`.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{exception_attributes, FatIOError};
+/// let error = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "my_file.txt".into());
+/// let attributes = exception_attributes(&error);
+/// assert!(attributes
+///     .iter()
+///     .any(|kv| kv.key.as_str() == "file.path" && kv.value.as_str() == "my_file.txt"));
+/// ```
+pub fn exception_attributes(error: &(dyn Error + 'static)) -> Vec<KeyValue> {
+    let causes: Vec<StringValue> = chain(error)
+        .skip(1)
+        .map(|e| StringValue::from(e.to_string()))
+        .collect();
+    let mut attributes = vec![
+        KeyValue::new("exception.type", type_name(error)),
+        KeyValue::new("exception.message", error.to_string()),
+        KeyValue::new("exception.causes", Value::Array(Array::String(causes))),
+    ];
+    if let Some(fat) = chain(error).find_map(|e| e.downcast_ref::<FatIOError>()) {
+        attributes.push(KeyValue::new(
+            "file.path",
+            fat.path().to_string_lossy().into_owned(),
+        ));
+    }
+    let correlation_id = chain(error)
+        .filter_map(|e| e.downcast_ref::<FatIOError>())
+        .find_map(FatIOError::correlation_id);
+    if let Some(id) = correlation_id {
+        attributes.push(KeyValue::new("correlation.id", id.to_string()));
+    }
+    attributes
+}
+
+/// record an error chain as `exception` event on `span`, see [`exception_attributes`]
+pub fn record_exception<S: Span>(span: &mut S, error: &(dyn Error + 'static)) {
+    span.add_event("exception", exception_attributes(error));
+}
+
+/// record an error chain as `exception` event on the currently active span, see [`exception_attributes`]
+pub fn record_exception_on_active_span(error: &(dyn Error + 'static)) {
+    opentelemetry::trace::get_active_span(|span| {
+        span.add_event("exception", exception_attributes(error))
+    });
+}
+
+/// record a [`MainError`] as `exception` event on `span`, including its backtrace as `exception.stacktrace` if one was captured
+pub fn record_main_error<S: Span, R>(span: &mut S, error: &MainError<R>) {
+    let mut attributes = exception_attributes(error.error());
+    if let Some(id) = error.correlation_id() {
+        attributes.retain(|kv| kv.key.as_str() != "correlation.id");
+        attributes.push(KeyValue::new("correlation.id", id.to_string()));
+    }
+    if error.backtrace().status() == BacktraceStatus::Captured {
+        attributes.push(KeyValue::new(
+            "exception.stacktrace",
+            error.backtrace().to_string(),
+        ));
+    }
+    span.add_event("exception", attributes);
+}
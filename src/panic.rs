@@ -0,0 +1,154 @@
+use crate::report::{Detail, Report};
+use crate::{capture_backtrace, MainError, MainErrorOptions, ThreadInfo, Verbosity};
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::panic::{PanicHookInfo, UnwindSafe};
+use std::sync::Once;
+
+/// # A panic converted into an error
+/// Created by [`catch_into_main_error`], by [`crate::fat_spawn_thread`] and for reports of the hook installed by [`install_panic_hook`].
+#[derive(Debug)]
+pub struct PanicError {
+    message: String,
+    location: Option<String>,
+}
+
+impl PanicError {
+    fn new(info: &PanicHookInfo) -> Self {
+        PanicError {
+            message: payload_message(info.payload()),
+            location: info.location().map(ToString::to_string),
+        }
+    }
+
+    pub(crate) fn unknown() -> Self {
+        PanicError {
+            message: "Box<dyn Any>".to_string(),
+            location: None,
+        }
+    }
+
+    /// the panic message, `Box<dyn Any>` if the payload was not a string
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// the source location of the panic as `file:line:column`, if known
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+}
+
+impl fmt::Display for PanicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "panicked at {location}: {}", self.message),
+            None => write!(f, "panicked: {}", self.message),
+        }
+    }
+}
+
+impl Error for PanicError {}
+
+/// the message of a panic payload, if it is a string as for `panic!` with a message
+fn payload_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+thread_local! {
+    /// the last panic on this thread, recorded by the hooks for [`catch_into_main_error`]
+    static LAST_PANIC: RefCell<Option<(PanicError, Backtrace)>> = const { RefCell::new(None) };
+}
+
+fn record_panic(info: &PanicHookInfo) {
+    let panic = (PanicError::new(info), capture_backtrace());
+    LAST_PANIC.with(|last| *last.borrow_mut() = Some(panic));
+}
+
+/// # Report panics in the style of [`crate::MainError`]
+/// Replaces the panic hook with one rendering the panic message with its location, the thread and, if enabled and captured, a backtrace with `options`,
+/// written to the output stream configured in `options`.
+/// # Examples
+/// ```rust, no_run
+/// use vg_errortools::{install_panic_hook, MainErrorOptions, Style};
+/// install_panic_hook(MainErrorOptions::new().style(Style::Fancy));
+/// panic!("mesh has no vertices");
+/// ```
+pub fn install_panic_hook(options: MainErrorOptions) {
+    std::panic::set_hook(Box::new(move |info: &PanicHookInfo| {
+        let error = PanicError::new(info);
+        let backtrace = capture_backtrace();
+        let details = vec![Detail {
+            key: "thread",
+            label: "thread",
+            value: ThreadInfo::current().to_string(),
+            verbosity: Verbosity::Detailed,
+        }];
+        let report = Report {
+            error: &error,
+            backtrace: Some(&backtrace),
+            details,
+        };
+        let _ = options.report(&report);
+        LAST_PANIC.with(|last| *last.borrow_mut() = Some((error, backtrace)));
+    }));
+}
+
+/// # Run a closure and convert a panic into a [`MainError`]
+/// Errors returned by `f` are converted into a `MainError` as with `?`, a panic is caught and becomes a [`PanicError`] with the panic location and the backtrace of the panic.
+/// The first call chains a hook in front of the current panic hook to record the location, the previous hook keeps running.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{catch_into_main_error, PanicError};
+/// let result: Result<(), _> = catch_into_main_error(|| -> Result<(), std::io::Error> {
+///     panic!("plugin crashed");
+/// });
+/// let error = result.unwrap_err();
+/// let panic = error.error().downcast_ref::<PanicError>().unwrap();
+/// assert_eq!(panic.message(), "plugin crashed");
+/// assert!(panic.location().unwrap().contains(".rs:"));
+/// ```
+#[track_caller]
+pub fn catch_into_main_error<T, E: Into<Box<dyn Error>>>(
+    f: impl FnOnce() -> Result<T, E> + UnwindSafe,
+) -> Result<T, MainError> {
+    match catch_panic(f) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(error)) => Err(MainError::from(error)),
+        Err((error, backtrace)) => Err(MainError::with_backtrace(Box::new(error), backtrace)),
+    }
+}
+
+/// run `f`, converting a panic into a [`PanicError`] with location and the backtrace of the panic
+pub(crate) fn catch_panic<T>(
+    f: impl FnOnce() -> T + UnwindSafe,
+) -> Result<T, (PanicError, Backtrace)> {
+    static CHAIN_HOOK: Once = Once::new();
+    CHAIN_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            record_panic(info);
+            previous(info);
+        }));
+    });
+    LAST_PANIC.with(|last| last.borrow_mut().take());
+    std::panic::catch_unwind(f).map_err(|payload| {
+        LAST_PANIC
+            .with(|last| last.borrow_mut().take())
+            .unwrap_or_else(|| {
+                let error = PanicError {
+                    message: payload_message(payload.as_ref()),
+                    location: None,
+                };
+                (error, Backtrace::disabled())
+            })
+    })
+}
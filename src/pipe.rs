@@ -0,0 +1,70 @@
+use crate::{chain, FatIOError, MainError};
+use std::io::ErrorKind;
+use std::sync::RwLock;
+
+static BROKEN_PIPE_EXIT_CODE: RwLock<Option<i32>> = RwLock::new(None);
+
+/// # Exit quietly with `code` when the output of the program was closed, e.g. by `tool | head`
+/// Once set, [`crate::run_main`] exits with `code` without a report for a [`MainError`] that [`MainError::is_broken_pipe`],
+/// [`MainError::exit_code`] returns `code` for it and [`MainError::report`] writes nothing.
+/// Common codes are `0` and `141`, the code of a shell process killed by `SIGPIPE`. `None`, the default, reports broken pipes like any other error.
+/// A `MainError` returned from `main` is still printed by the standard library, its `Debug` output never exits the process.
+/// Call this once at startup.
+/// # Examples
+/// ```rust, no_run
+/// use std::io::Write;
+/// use vg_errortools::{run_main, set_broken_pipe_exit_code};
+/// fn main() {
+///     set_broken_pipe_exit_code(Some(0));
+///     run_main(|| {
+///         let mut stdout = std::io::stdout().lock();
+///         for i in 0.. {
+///             writeln!(stdout, "{i}")?;
+///         }
+///         Ok::<_, std::io::Error>(())
+///     })
+/// }
+/// ```
+pub fn set_broken_pipe_exit_code(code: Option<i32>) {
+    *BROKEN_PIPE_EXIT_CODE
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = code;
+}
+
+/// the exit code set by [`set_broken_pipe_exit_code`]
+pub fn broken_pipe_exit_code() -> Option<i32> {
+    *BROKEN_PIPE_EXIT_CODE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+impl<R> MainError<R> {
+    /// # Whether this error was caused by writing to a closed pipe like stdout or stderr
+    /// That is a [`ErrorKind::BrokenPipe`] io error in the chain without a [`FatIOError`],
+    /// broken pipes of named files or sockets are not treated as closed output.
+    /// # Examples
+    /// ```rust
+    /// use std::io::ErrorKind;
+    /// use vg_errortools::{FatIOError, MainError};
+    /// assert!(MainError::from(std::io::Error::from(ErrorKind::BrokenPipe)).is_broken_pipe());
+    /// let fat = FatIOError::from_std_io_err(ErrorKind::BrokenPipe.into(), "/run/viewer.sock".into());
+    /// assert!(!MainError::from(fat).is_broken_pipe());
+    /// ```
+    pub fn is_broken_pipe(&self) -> bool {
+        let mut broken_pipe = false;
+        for e in chain(self.error()) {
+            if e.is::<FatIOError>() {
+                return false;
+            }
+            broken_pipe |= e
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|e| e.kind() == ErrorKind::BrokenPipe);
+        }
+        broken_pipe
+    }
+
+    /// the exit code to quietly exit with instead of reporting this error, see [`set_broken_pipe_exit_code`]
+    pub(crate) fn broken_pipe_exit(&self) -> Option<i32> {
+        broken_pipe_exit_code().filter(|_| self.is_broken_pipe())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{chain, FatIOError, MainError};
+use pyo3::exceptions::{PyOSError, PyRuntimeError};
+use pyo3::prelude::*;
+use pyo3::types::PyModule;
+use std::error::Error;
+
+pyo3::create_exception!(
+    vg_errortools,
+    PyFatIOError,
+    PyOSError,
+    "An OSError with the `path` of the file it failed on and the messages of the error `chain`."
+);
+pyo3::create_exception!(
+    vg_errortools,
+    PyMainError,
+    PyRuntimeError,
+    "A RuntimeError with the messages of the error `chain` and the `path` of the first failed file, if any."
+);
+
+/// # Add the exception types to a Python module
+/// Registers `PyFatIOError` and `PyMainError`, so Python code can catch them by type.
+pub fn add_exception_types(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = module.py();
+    module.add("PyFatIOError", py.get_type::<PyFatIOError>())?;
+    module.add("PyMainError", py.get_type::<PyMainError>())?;
+    Ok(())
+}
+
+/// the messages of the error and all of its causes
+fn messages(error: &(dyn Error + 'static)) -> Vec<String> {
+    chain(error).map(ToString::to_string).collect()
+}
+
+/// set the `path` and `chain` attributes on the exception value
+fn with_attributes(error: PyErr, path: Option<String>, chain: Vec<String>) -> PyErr {
+    Python::attach(|py| {
+        let value = error.value(py);
+        let _ = value.setattr("path", path);
+        let _ = value.setattr("chain", chain);
+    });
+    error
+}
+
+/// Raises a `PyFatIOError`, an `OSError` with `errno`, `strerror` and `filename` set, plus the attributes `path` and `chain`
+impl From<FatIOError> for PyErr {
+    fn from(e: FatIOError) -> Self {
+        let path = e.path().to_string_lossy().into_owned();
+        let errno = e
+            .source()
+            .and_then(|source| source.downcast_ref::<std::io::Error>())
+            .and_then(std::io::Error::raw_os_error);
+        let error = PyFatIOError::new_err((errno, e.to_string(), path.clone()));
+        with_attributes(error, Some(path), messages(&e))
+    }
+}
+
+/// Raises a `PyMainError` with the message of the error and the attributes `path` and `chain`
+/// # Examples
+/// ```rust
+/// use pyo3::prelude::*;
+/// use vg_errortools::{FatIOError, MainError};
+/// Python::initialize();
+/// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "mesh.stl".into());
+/// let error = PyErr::from(MainError::from(fat));
+/// Python::attach(|py| {
+///     let value = error.value(py);
+///     assert_eq!(value.getattr("path").unwrap().extract::<String>().unwrap(), "mesh.stl");
+///     assert_eq!(value.getattr("chain").unwrap().len().unwrap(), 2);
+/// });
+/// ```
+impl<R> From<MainError<R>> for PyErr {
+    fn from(e: MainError<R>) -> Self {
+        let path = chain(e.error())
+            .find_map(|cause| cause.downcast_ref::<FatIOError>())
+            .map(|fat| fat.path().to_string_lossy().into_owned());
+        let error = PyMainError::new_err(e.to_string());
+        with_attributes(error, path, messages(e.error()))
+    }
+}
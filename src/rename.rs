@@ -0,0 +1,140 @@
+use crate::template::display_path;
+use crate::{canonical, fat_copy_with_progress, fat_create_new, FatIOError};
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// # A failed rename with both paths
+/// The io error of a rename does not tell whether the source or the destination is at fault, so both are kept.
+/// Created by [`fat_rename_or_copy`] and `fat_tokio_rename`, the source is the io error of the rename
+/// or, for a file copied between filesystems, the [`FatIOError`] of the step that failed.
+#[derive(Debug)]
+pub struct FatRenameError {
+    from: PathBuf,
+    to: PathBuf,
+    kind: ErrorKind,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl FatRenameError {
+    pub(crate) fn new(from: PathBuf, to: PathBuf, source: std::io::Error) -> Self {
+        FatRenameError {
+            from,
+            to,
+            kind: source.kind(),
+            source: Box::new(source),
+        }
+    }
+
+    /// the path renamed from
+    pub fn from_path(&self) -> &Path {
+        &self.from
+    }
+
+    /// the path renamed to
+    pub fn to_path(&self) -> &Path {
+        &self.to
+    }
+
+    /// the kind of the io error
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl Display for FatRenameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "renaming '{}' to '{}' failed with error {}",
+            display_path(&self.from),
+            display_path(&self.to),
+            self.source
+        )
+    }
+}
+
+impl Error for FatRenameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// keeps the kind
+impl From<FatRenameError> for std::io::Error {
+    fn from(e: FatRenameError) -> Self {
+        std::io::Error::new(e.kind(), e)
+    }
+}
+
+/// distinguishes the temporary files of concurrent calls in one process
+static TEMPORARY: AtomicUsize = AtomicUsize::new(0);
+
+/// # Rename a file, copying it if source and destination are on different filesystems
+/// A rename cannot move files across filesystems and fails with `CrossesDevices` (`EXDEV`), e.g. from `/tmp` to a mounted volume.
+/// Then the file is copied into a new temporary `.tmp…` file next to the destination, its permissions are applied to the copy,
+/// the copy is renamed into place and the source is removed. Errors name both paths, the error of a failed step as source names the file it failed on.
+/// A failed copy removes only the temporary file, so the destination is either replaced completely or left as it was.
+/// Directories can only be renamed, not copied.
+/// # Examples
+/// ```rust
+/// use vg_errortools::fat_rename_or_copy;
+/// let from = std::env::temp_dir().join("vg_errortools_rename_doc.raw");
+/// let to = std::env::temp_dir().join("vg_errortools_rename_doc_moved.raw");
+/// std::fs::write(&from, "scan").unwrap();
+/// fat_rename_or_copy(&from, &to).unwrap();
+/// assert_eq!(std::fs::read_to_string(&to).unwrap(), "scan");
+/// let error = fat_rename_or_copy(&from, &to).unwrap_err();
+/// assert_eq!((error.from_path(), error.to_path()), (from.as_path(), to.as_path()));
+/// assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+/// # std::fs::remove_file(to).unwrap();
+/// ```
+pub fn fat_rename_or_copy(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+) -> Result<(), FatRenameError> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let recorded = |path| canonical::recorded_path(path).to_path_buf();
+    match std::fs::rename(from, to) {
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {}
+        result => return result.map_err(|e| FatRenameError::new(recorded(from), recorded(to), e)),
+    }
+    copy_and_remove(from, to).map_err(|e| FatRenameError {
+        from: recorded(from),
+        to: recorded(to),
+        kind: e.kind(),
+        source: Box::new(e),
+    })
+}
+
+/// the steps of [`fat_rename_or_copy`] for files on different filesystems
+fn copy_and_remove(from: &Path, to: &Path) -> Result<(), FatIOError> {
+    let fat = |e, path: &Path, operation| {
+        FatIOError::from_shared_path(e, canonical::recorded_path(path)).with_operation(operation)
+    };
+    let permissions = std::fs::metadata(from)
+        .map_err(|e| fat(e, from, "std::fs::metadata"))?
+        .permissions();
+    let name = to.file_name().unwrap_or_default().to_string_lossy();
+    let temporary = to.with_file_name(format!(
+        ".tmp{}-{}-{name}",
+        std::process::id(),
+        TEMPORARY.fetch_add(1, Ordering::Relaxed)
+    ));
+    fat_create_new(&temporary)?;
+    let moved = fat_copy_with_progress(from, &temporary, |_, _| {})
+        .map_err(|e| FatIOError::from(e).with_operation("std::fs::copy"))
+        .and_then(|_| {
+            std::fs::set_permissions(&temporary, permissions)
+                .map_err(|e| fat(e, &temporary, "std::fs::set_permissions"))
+        })
+        .and_then(|()| std::fs::rename(&temporary, to).map_err(|e| fat(e, to, "std::fs::rename")));
+    if let Err(e) = moved {
+        let _ = std::fs::remove_file(&temporary);
+        return Err(e);
+    }
+    std::fs::remove_file(from).map_err(|e| fat(e, from, "std::fs::remove_file"))
+}
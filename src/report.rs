@@ -0,0 +1,726 @@
+use crate::{chain, end_user_message, FatIOError, PanicError};
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The stream a [`crate::MainError`] report is written to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputStream {
+    /// standard error, the default
+    #[default]
+    Stderr,
+    /// standard output
+    Stdout,
+}
+
+impl OutputStream {
+    fn is_terminal(self) -> bool {
+        match self {
+            OutputStream::Stderr => std::io::stderr().is_terminal(),
+            OutputStream::Stdout => std::io::stdout().is_terminal(),
+        }
+    }
+
+    /// the width of the terminal the stream is connected to, in columns
+    fn terminal_width(self) -> Option<usize> {
+        #[cfg(any(unix, windows))]
+        {
+            let size = match self {
+                OutputStream::Stderr => terminal_size::terminal_size_of(std::io::stderr()),
+                OutputStream::Stdout => terminal_size::terminal_size_of(std::io::stdout()),
+            };
+            size.map(|(terminal_size::Width(width), _)| usize::from(width))
+        }
+        #[cfg(not(any(unix, windows)))]
+        None
+    }
+
+    fn write_all(self, text: &str) -> std::io::Result<()> {
+        match self {
+            OutputStream::Stderr => std::io::stderr().lock().write_all(text.as_bytes()),
+            OutputStream::Stdout => std::io::stdout().lock().write_all(text.as_bytes()),
+        }
+    }
+}
+
+/// Whether a report uses ANSI colors
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// never color, the default
+    #[default]
+    Never,
+    /// always color
+    Always,
+    /// color if the output stream is a terminal and `NO_COLOR` is not set
+    Auto,
+}
+
+/// # The layout of a rendered report
+/// Selected per report with [`MainErrorOptions::style`] or process wide with [`set_error_style`].
+/// # Examples
+/// ```rust
+/// use vg_errortools::{ColorChoice, ContextError, FatIOError, MainError, MainErrorOptions, Style};
+/// let fat = FatIOError::from_std_io_err(std::io::Error::other("disk on fire"), "my_file.txt".into());
+/// let error: MainError = fat.into();
+/// let fancy = MainErrorOptions::new().style(Style::Fancy).color(ColorChoice::Never);
+/// assert_eq!(
+///     error.render(&fancy),
+///     "Operating on file 'my_file.txt' failed with error disk on fire\n  ╰─ disk on fire\ncode: VG-IO-0000"
+/// );
+/// let fat = FatIOError::from_std_io_err(std::io::Error::other("disk on fire"), "my_file.txt".into());
+/// let error = MainError::from(ContextError::new("loading the scene failed", fat));
+/// assert_eq!(
+///     error.render(&MainErrorOptions::new().style(Style::Numbered)),
+///     "loading the scene failed\ncaused by:\n  1: Operating on file 'my_file.txt' failed with error disk on fire\n  2: disk on fire\ncode: VG-IO-0000"
+/// );
+/// assert_eq!(
+///     error.render(&MainErrorOptions::new().style(Style::Numbered).max_depth(Some(0))),
+///     "loading the scene failed\ncaused by:\n  … and 2 more causes, set VG_ERROR_VERBOSITY=2 to show all\ncode: VG-IO-0000"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Style {
+    /// the message and one `caused by:` line per cause, the default
+    #[default]
+    Plain,
+    /// the message and all causes joined by `: ` on a single line
+    Compact,
+    /// a tree of the message and its causes, colored if the output stream is a terminal unless configured otherwise
+    Fancy,
+    /// a single line JSON object with `message` and `causes` (and `backtrace` if enabled and captured)
+    Json,
+    /// the message and a `caused by:` block of numbered, indented causes, easy to reference in support conversations
+    Numbered,
+    /// # A short friendly message for non-technical users instead of the cause chain, see [`crate::set_end_user_message`]
+    /// The crash report note of [`crate::set_crash_report_dir`] still points to the full report.
+    /// From [`Verbosity::Detailed`] on, e.g. with `VG_ERROR_VERBOSITY=2`, developers get the [`Style::Plain`] report.
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::{FatIOError, MainError, MainErrorOptions, Style, Verbosity};
+    /// let fat = FatIOError::from_std_io_err(std::io::Error::other("disk on fire"), "my_file.txt".into());
+    /// let error = MainError::from(fat);
+    /// let end_user = MainErrorOptions::new().style(Style::EndUser);
+    /// std::env::remove_var("VG_ERROR_VERBOSITY");
+    /// assert_eq!(
+    ///     error.render(&end_user),
+    ///     "The application ran into a problem and had to stop.\nSet VG_ERROR_VERBOSITY=2 to show the technical details."
+    /// );
+    /// assert!(error.render(&end_user.verbosity(Verbosity::Detailed)).contains("\ncaused by: disk on fire"));
+    /// ```
+    EndUser,
+}
+
+impl Style {
+    /// # The style named `name`, `None` for unknown names
+    /// The names are `plain`, `compact`, `fancy`, `json`, `numbered` and `end-user`, e.g. to select the style from a command line flag.
+    pub fn from_name(name: &str) -> Option<Style> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "plain" => Some(Style::Plain),
+            "compact" => Some(Style::Compact),
+            "fancy" => Some(Style::Fancy),
+            "json" => Some(Style::Json),
+            "numbered" => Some(Style::Numbered),
+            "end-user" => Some(Style::EndUser),
+            _ => None,
+        }
+    }
+}
+
+static GLOBAL_STYLE: AtomicU8 = AtomicU8::new(Style::Plain as u8);
+
+/// # Select the process wide report style
+/// It is used by the `Debug` output of [`crate::MainError`] and by all [`MainErrorOptions`] without an explicit style.
+/// Call this once at startup.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{set_error_style, MainError, Style};
+/// set_error_style(Style::Json);
+/// let error: MainError = std::io::Error::other("disk on fire").into();
+/// assert_eq!(format!("{error:?}"), r#"{"message":"disk on fire","causes":[]}"#);
+/// ```
+pub fn set_error_style(style: Style) {
+    GLOBAL_STYLE.store(style as u8, Ordering::Relaxed);
+}
+
+/// the process wide report style, see [`set_error_style`]
+pub fn error_style() -> Style {
+    match GLOBAL_STYLE.load(Ordering::Relaxed) {
+        1 => Style::Compact,
+        2 => Style::Fancy,
+        3 => Style::Json,
+        4 => Style::Numbered,
+        5 => Style::EndUser,
+        _ => Style::Plain,
+    }
+}
+
+/// The environment variable end users can set to `0`..`3` to select a [`Verbosity`] without rebuilding the binary
+pub const VERBOSITY_ENV_VAR: &str = "VG_ERROR_VERBOSITY";
+
+/// # How much of an error a report shows
+/// Applications pick a default with [`MainErrorOptions::verbosity`], the environment variable [`VERBOSITY_ENV_VAR`] overrides it.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{FatIOError, MainError, MainErrorOptions, Verbosity};
+/// let fat = FatIOError::from_std_io_err(std::io::Error::other("disk on fire"), "my_file.txt".into());
+/// let error: MainError = fat.into();
+/// let terse = MainErrorOptions::new().verbosity(Verbosity::Terse);
+/// std::env::remove_var("VG_ERROR_VERBOSITY");
+/// assert_eq!(error.render(&terse), "Operating on file 'my_file.txt' failed with error disk on fire");
+/// std::env::set_var("VG_ERROR_VERBOSITY", "1");
+/// assert!(error.render(&terse).contains("caused by: disk on fire"));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// `0`: only the top message on one line
+    Terse,
+    /// `1`: the message and the cause chain as configured, the default
+    #[default]
+    Normal,
+    /// `2`: the whole cause chain, ignoring [`MainErrorOptions::max_depth`]
+    Detailed,
+    /// `3`: the whole cause chain and a backtrace, which is captured even without `RUST_BACKTRACE`
+    Full,
+}
+
+impl Verbosity {
+    /// the verbosity requested by [`VERBOSITY_ENV_VAR`], `None` if unset or not in `0..=3`
+    pub fn from_env() -> Option<Verbosity> {
+        match std::env::var(VERBOSITY_ENV_VAR).ok()?.trim() {
+            "0" => Some(Verbosity::Terse),
+            "1" => Some(Verbosity::Normal),
+            "2" => Some(Verbosity::Detailed),
+            "3" => Some(Verbosity::Full),
+            _ => None,
+        }
+    }
+}
+
+/// # Configuration for rendering a [`crate::MainError`] report
+/// The default reproduces the `Debug` output of `MainError` without a [`crate::Reporter`], which follows the process wide [`Style`].
+/// Causes with the same message as the error or cause directly above them, typical for thin newtype wrappers, are only shown once.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{MainError, MainErrorOptions};
+/// let error: MainError = std::io::Error::other("disk on fire").into();
+/// let options = MainErrorOptions::new().single_line(true);
+/// assert_eq!(error.render(&options), "disk on fire");
+/// ```
+///
+/// ```rust
+/// use vg_errortools::{FatIOError, MainError, MainErrorOptions};
+/// let fat = FatIOError::from_std_io_err(std::io::Error::other("disk on fire"), "my_file.txt".into());
+/// let error: MainError = fat.into();
+/// assert_eq!(
+///     error.render(&MainErrorOptions::new()),
+///     "Operating on file 'my_file.txt' failed with error disk on fire\ncaused by: disk on fire\ncode: VG-IO-0000"
+/// );
+/// assert_eq!(
+///     error.render(&MainErrorOptions::new().max_depth(Some(0))),
+///     "Operating on file 'my_file.txt' failed with error disk on fire\ncaused by: … and 1 more cause, set VG_ERROR_VERBOSITY=2 to show all\ncode: VG-IO-0000"
+/// );
+/// ```
+///
+/// ```rust
+/// use vg_errortools::{ContextError, MainError, MainErrorOptions};
+/// let wrapped = ContextError::new("loading the scene failed", std::io::Error::other("disk on fire"));
+/// let error = MainError::from(ContextError::new("loading the scene failed", wrapped));
+/// assert_eq!(
+///     error.render(&MainErrorOptions::new()),
+///     "loading the scene failed\ncaused by: disk on fire"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MainErrorOptions {
+    style: Option<Style>,
+    color: Option<ColorChoice>,
+    max_depth: Option<usize>,
+    single_line: bool,
+    backtrace: bool,
+    verbosity: Option<Verbosity>,
+    github_annotations: Option<bool>,
+    wrap_width: Option<Option<usize>>,
+    ignore_env: bool,
+    stream: OutputStream,
+}
+
+const RED_BOLD: &str = "\x1b[1;31m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+impl MainErrorOptions {
+    /// the default options, see [`MainErrorOptions`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// set the layout, overriding the process wide style
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// set whether ANSI colors are used, without this only [`Style::Fancy`] colors terminal output
+    pub fn color(mut self, color: ColorChoice) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// # Limit the number of printed causes, `None` prints the whole chain
+    /// Left out causes are summarized in a last line with a hint on [`VERBOSITY_ENV_VAR`], JSON reports count them in `elided_causes`.
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::{ContextError, MainError, MainErrorOptions};
+    /// let error = MainError::from(ContextError::new(
+    ///     "exporting failed",
+    ///     ContextError::new("writing the mesh failed", std::io::Error::other("disk on fire")),
+    /// ));
+    /// assert_eq!(
+    ///     error.render(&MainErrorOptions::new().max_depth(Some(0))),
+    ///     "exporting failed\ncaused by: … and 2 more causes, set VG_ERROR_VERBOSITY=2 to show all"
+    /// );
+    /// ```
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// render the message and all causes joined by `: ` on a single line, turns [`Style::Plain`] and [`Style::Numbered`] into [`Style::Compact`]
+    pub fn single_line(mut self, single_line: bool) -> Self {
+        self.single_line = single_line;
+        self
+    }
+
+    /// append the backtrace captured when the `MainError` was created, if `RUST_BACKTRACE` enabled capturing
+    pub fn backtrace(mut self, backtrace: bool) -> Self {
+        self.backtrace = backtrace;
+        self
+    }
+
+    /// set the default verbosity, [`VERBOSITY_ENV_VAR`] takes precedence if set
+    pub fn verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = Some(verbosity);
+        self
+    }
+
+    /// append a GitHub Actions `::error` workflow command to human readable reports, so the failure shows up as annotation in the checks.
+    /// The annotation points to the location of a panic or the file of a [`crate::FatIOError`] in the chain.
+    /// Its message is the error itself, causes are only repeated there if the depth limit left them out of the report.
+    /// Enabled by default if the environment variable `GITHUB_ACTIONS` is `true`.
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::{FatIOError, MainError, MainErrorOptions};
+    /// let fat = FatIOError::from_std_io_err(std::io::Error::other("disk on fire"), "data/mesh.stl".into());
+    /// let error = MainError::from(fat);
+    /// let report = error.render(&MainErrorOptions::new().github_annotations(true));
+    /// assert!(report.ends_with(
+    ///     "\ncaused by: disk on fire\ncode: VG-IO-0000\n::error file=data/mesh.stl::Operating on file 'data/mesh.stl' failed with error disk on fire"
+    /// ));
+    /// ```
+    pub fn github_annotations(mut self, enabled: bool) -> Self {
+        self.github_annotations = Some(enabled);
+        self
+    }
+
+    /// # Soft-wrap long lines at `width` columns with hanging indentation, `None` disables wrapping
+    /// Words, e.g. long paths, are never split. Without this, reports written to a terminal wrap at the width of the terminal,
+    /// or of the `COLUMNS` environment variable if the size cannot be queried.
+    /// Compact and JSON reports are never wrapped.
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::{MainError, MainErrorOptions};
+    /// let error = MainError::from(std::io::Error::other("the export of the scene failed"));
+    /// assert_eq!(
+    ///     error.render(&MainErrorOptions::new().wrap_width(Some(16))),
+    ///     "the export of\n  the scene\n  failed"
+    /// );
+    /// ```
+    pub fn wrap_width(mut self, width: Option<usize>) -> Self {
+        self.wrap_width = Some(width);
+        self
+    }
+
+    fn effective_wrap_width(&self) -> Option<usize> {
+        self.wrap_width.unwrap_or_else(|| {
+            if !self.stream.is_terminal() {
+                return None;
+            }
+            self.stream
+                .terminal_width()
+                .or_else(|| std::env::var("COLUMNS").ok()?.trim().parse().ok())
+        })
+    }
+
+    /// set the stream [`crate::MainError::report`] writes to
+    pub fn stream(mut self, stream: OutputStream) -> Self {
+        self.stream = stream;
+        self
+    }
+
+    /// use the configured verbosity regardless of [`VERBOSITY_ENV_VAR`]
+    pub(crate) fn ignore_env(mut self) -> Self {
+        self.ignore_env = true;
+        self
+    }
+
+    fn effective_verbosity(&self) -> Verbosity {
+        let from_env = if self.ignore_env {
+            None
+        } else {
+            Verbosity::from_env()
+        };
+        from_env.or(self.verbosity).unwrap_or_default()
+    }
+
+    fn effective_style(&self) -> Style {
+        let verbosity = self.effective_verbosity();
+        let terse = verbosity == Verbosity::Terse;
+        match self.style.unwrap_or_else(error_style) {
+            Style::EndUser if verbosity >= Verbosity::Detailed => Style::Plain,
+            Style::Plain | Style::Numbered if self.single_line || terse => Style::Compact,
+            style => style,
+        }
+    }
+
+    fn use_color(&self, style: Style) -> bool {
+        let default = match style {
+            Style::Fancy => ColorChoice::Auto,
+            _ => ColorChoice::Never,
+        };
+        match self.color.unwrap_or(default) {
+            ColorChoice::Never => false,
+            ColorChoice::Always => true,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none() && self.stream.is_terminal()
+            }
+        }
+    }
+
+    /// the causes to show and the ones left out by the depth limit
+    fn causes<'a>(&self, error: &'a dyn Error) -> (Vec<&'a dyn Error>, Vec<&'a dyn Error>) {
+        let max_depth = match self.effective_verbosity() {
+            Verbosity::Terse => Some(0),
+            Verbosity::Normal => self.max_depth,
+            Verbosity::Detailed | Verbosity::Full => None,
+        };
+        let mut previous = error.to_string();
+        let mut causes: Vec<_> = std::iter::successors(error.source(), |&cause| cause.source())
+            .filter(|cause| {
+                let message = cause.to_string();
+                let duplicate = message == previous;
+                previous = message;
+                !duplicate
+            })
+            .collect();
+        let shown = causes.len().min(max_depth.unwrap_or(usize::MAX));
+        let elided = causes.split_off(shown);
+        (causes, elided)
+    }
+
+    /// the line summarizing causes left out by the depth limit, none in terse reports
+    fn elided_summary(&self, elided: usize) -> Option<String> {
+        if elided == 0 || self.effective_verbosity() == Verbosity::Terse {
+            return None;
+        }
+        let causes = if elided == 1 { "cause" } else { "causes" };
+        Some(format!(
+            "… and {elided} more {causes}, set {VERBOSITY_ENV_VAR}=2 to show all"
+        ))
+    }
+
+    fn visible<'a>(&self, details: &'a [Detail]) -> impl Iterator<Item = &'a Detail> {
+        let verbosity = self.effective_verbosity();
+        details.iter().filter(move |d| d.verbosity <= verbosity)
+    }
+
+    fn captured<'a>(&self, backtrace: Option<&'a Backtrace>) -> Option<&'a Backtrace> {
+        let enabled = self.backtrace || self.effective_verbosity() == Verbosity::Full;
+        backtrace.filter(|b| enabled && b.status() == BacktraceStatus::Captured)
+    }
+
+    pub(crate) fn render(&self, report: &Report, f: &mut dyn fmt::Write) -> fmt::Result {
+        let &Report {
+            error,
+            backtrace,
+            ref details,
+        } = report;
+        let style = self.effective_style();
+        if style == Style::Json {
+            return self.render_json(report, f);
+        }
+        let color = self.use_color(style);
+        let width = match style {
+            Style::Compact => None,
+            _ => self.effective_wrap_width(),
+        };
+        if style == Style::EndUser {
+            let message = end_user_message();
+            let message = wrap_text(&message, 0, 0, width);
+            let hint = format!("Set {VERBOSITY_ENV_VAR}=2 to show the technical details.");
+            let hint = wrap_text(&hint, 0, 0, width);
+            return match color {
+                true => write!(f, "{RED_BOLD}{message}{RESET}\n{DIM}{hint}{RESET}"),
+                false => write!(f, "{message}\n{hint}"),
+            };
+        }
+        let message = error.to_string();
+        let message = wrap_text(&message, 0, 2, width);
+        if color {
+            write!(f, "{RED_BOLD}{message}{RESET}")?;
+        } else {
+            write!(f, "{message}")?;
+        }
+        let (causes, elided) = self.causes(error);
+        let summary = self.elided_summary(elided.len());
+        let caused_by = caused_by();
+        if style == Style::Numbered {
+            write_numbered_causes(f, &causes, summary.as_deref(), color, width)?;
+        } else {
+            let mut causes = causes
+                .into_iter()
+                .map(|cause| cause.to_string())
+                .chain(summary)
+                .peekable();
+            let caused_by_width = caused_by.chars().count() + 1;
+            while let Some(cause) = causes.next() {
+                let branch = if causes.peek().is_some() {
+                    "├─"
+                } else {
+                    "╰─"
+                };
+                let indent = match style {
+                    Style::Fancy => 5,
+                    _ => caused_by_width,
+                };
+                let cause = wrap_text(&cause, indent, indent, width);
+                match (style, color) {
+                    (Style::Compact, _) => write!(f, ": {cause}")?,
+                    (Style::Fancy, true) => write!(f, "\n  {DIM}{branch}{RESET} {cause}")?,
+                    (Style::Fancy, false) => write!(f, "\n  {branch} {cause}")?,
+                    (_, true) => write!(f, "\n{DIM}{caused_by}{RESET} {cause}")?,
+                    (_, false) => write!(f, "\n{caused_by} {cause}")?,
+                }
+            }
+        }
+        for detail in self.visible(details) {
+            let label = detail.label;
+            let value = wrap_text(&detail.value, label.len() + 2, label.len() + 2, width);
+            match (style, color) {
+                (Style::Compact, _) => write!(f, " [{label}: {value}]")?,
+                (_, true) => write!(f, "\n{DIM}{label}:{RESET} {value}")?,
+                (_, false) => write!(f, "\n{label}: {value}")?,
+            }
+        }
+        if let Some(backtrace) = self.captured(backtrace) {
+            write!(f, "\n\nbacktrace:\n{backtrace}")?;
+        }
+        if self.use_github_annotations() {
+            f.write_char('\n')?;
+            write_github_annotation(f, error, &elided)?;
+        }
+        Ok(())
+    }
+
+    fn use_github_annotations(&self) -> bool {
+        self.github_annotations
+            .unwrap_or_else(|| std::env::var_os("GITHUB_ACTIONS").is_some_and(|v| v == "true"))
+    }
+
+    fn render_json(&self, report: &Report, f: &mut dyn fmt::Write) -> fmt::Result {
+        let &Report {
+            error,
+            backtrace,
+            ref details,
+        } = report;
+        f.write_str("{\"message\":")?;
+        write_json_string(f, &error.to_string())?;
+        f.write_str(",\"causes\":[")?;
+        let (causes, elided) = self.causes(error);
+        for (i, cause) in causes.into_iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            write_json_string(f, &cause.to_string())?;
+        }
+        f.write_char(']')?;
+        if !elided.is_empty() {
+            write!(f, ",\"elided_causes\":{}", elided.len())?;
+        }
+        for detail in self.visible(details) {
+            write!(f, ",\"{}\":", detail.key)?;
+            write_json_string(f, &detail.value)?;
+        }
+        if let Some(backtrace) = self.captured(backtrace) {
+            f.write_str(",\"backtrace\":")?;
+            write_json_string(f, &backtrace.to_string())?;
+        }
+        f.write_char('}')
+    }
+
+    pub(crate) fn render_to_string(&self, report: &Report) -> String {
+        let mut text = String::new();
+        let _ = self.render(report, &mut text);
+        text
+    }
+
+    pub(crate) fn report(&self, report: &Report) -> std::io::Result<()> {
+        let mut text = self.render_to_string(report);
+        text.push('\n');
+        crate::sink::tee(&text);
+        self.stream.write_all(&text)
+    }
+}
+
+/// A labeled piece of information shown below the cause chain of a report
+pub(crate) struct Detail {
+    /// the key in JSON output
+    pub(crate) key: &'static str,
+    /// the label in human readable output
+    pub(crate) label: &'static str,
+    pub(crate) value: String,
+    /// the detail is only shown from this verbosity on
+    pub(crate) verbosity: Verbosity,
+}
+
+/// Everything a report is rendered from
+pub(crate) struct Report<'a> {
+    pub(crate) error: &'a (dyn Error + 'static),
+    pub(crate) backtrace: Option<&'a Backtrace>,
+    pub(crate) details: Vec<Detail>,
+}
+
+/// greedily wrap `text` at spaces to lines of at most `width` columns, the first line starts at column `first`, continuation lines are indented by `hanging`
+fn wrap_text(text: &str, first: usize, hanging: usize, width: Option<usize>) -> Cow<'_, str> {
+    let Some(width) = width else {
+        return text.into();
+    };
+    if first + text.chars().count() <= width || text.contains('\n') {
+        return text.into();
+    }
+    let mut wrapped = String::with_capacity(text.len());
+    let (mut column, mut line_empty) = (first, true);
+    for word in text.split(' ') {
+        let len = word.chars().count();
+        if !line_empty && column + 1 + len > width {
+            wrapped.push('\n');
+            wrapped.extend(std::iter::repeat_n(' ', hanging));
+            (column, line_empty) = (hanging, true);
+        }
+        if !line_empty {
+            wrapped.push(' ');
+            column += 1;
+        }
+        wrapped.push_str(word);
+        column += len;
+        line_empty = false;
+    }
+    wrapped.into()
+}
+
+/// escape data of a GitHub workflow command, properties additionally escape `:` and `,`
+fn escape_workflow_command(text: &str, property: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '%' => escaped.push_str("%25"),
+            '\r' => escaped.push_str("%0D"),
+            '\n' => escaped.push_str("%0A"),
+            ':' if property => escaped.push_str("%3A"),
+            ',' if property => escaped.push_str("%2C"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// the `caused by:` header and one numbered line per cause for [`Style::Numbered`], followed by the summary of left out causes
+fn write_numbered_causes(
+    f: &mut dyn fmt::Write,
+    causes: &[&dyn Error],
+    summary: Option<&str>,
+    color: bool,
+    width: Option<usize>,
+) -> fmt::Result {
+    if causes.is_empty() && summary.is_none() {
+        return Ok(());
+    }
+    let caused_by = caused_by();
+    if color {
+        write!(f, "\n{DIM}{caused_by}{RESET}")?;
+    } else {
+        write!(f, "\n{caused_by}")?;
+    }
+    let digits = causes.len().to_string().len();
+    for (i, cause) in causes.iter().enumerate() {
+        let cause = cause.to_string();
+        let cause = wrap_text(&cause, digits + 4, digits + 4, width);
+        write!(f, "\n  {:>digits$}: {cause}", i + 1)?;
+    }
+    if let Some(summary) = summary {
+        write!(f, "\n  {}", wrap_text(summary, 2, 2, width))?;
+    }
+    Ok(())
+}
+
+/// the annotation message holds the error and only the causes the report left out, the others are printed above already
+fn write_github_annotation(
+    f: &mut dyn fmt::Write,
+    error: &(dyn Error + 'static),
+    elided: &[&dyn Error],
+) -> fmt::Result {
+    let panic_location = chain(error)
+        .find_map(|e| e.downcast_ref::<PanicError>())
+        .and_then(PanicError::location)
+        .and_then(|location| {
+            let mut parts = location.rsplitn(3, ':');
+            let (_column, line, file) = (parts.next()?, parts.next()?, parts.next()?);
+            Some((file.to_string(), Some(line.to_string())))
+        });
+    let target = panic_location.or_else(|| {
+        chain(error)
+            .find_map(|e| e.downcast_ref::<FatIOError>())
+            .map(|fat| (fat.path().to_string_lossy().into_owned(), None))
+    });
+    f.write_str("::error")?;
+    if let Some((file, line)) = target {
+        write!(f, " file={}", escape_workflow_command(&file, true))?;
+        if let Some(line) = line {
+            write!(f, ",line={line}")?;
+        }
+    }
+    let mut message = error.to_string();
+    for cause in elided {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+    }
+    write!(f, "::{}", escape_workflow_command(&message, false))
+}
+
+/// the label in front of each cause in [`Style::Plain`]
+fn caused_by() -> Cow<'static, str> {
+    #[cfg(feature = "i18n")]
+    if let Some(phrase) = crate::i18n::phrase("caused-by", &[]) {
+        return phrase.into();
+    }
+    "caused by:".into()
+}
+
+pub(crate) fn write_json_string(f: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in text.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
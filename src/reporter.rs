@@ -0,0 +1,113 @@
+use crate::{MainError, MainErrorOptions, Style};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// # Render the report of a [`MainError`]
+/// Install an implementation with [`set_reporter`] to change how errors returned from `main` and [`crate::run_main`] are printed.
+/// [`Style`] and [`MainErrorOptions`] are the built-in reporters, so deployments of the same binary can pick a format at runtime.
+/// # Examples
+/// ```rust
+/// use std::fmt;
+/// use vg_errortools::{set_reporter, MainError, Reporter, Style};
+/// struct Syslog;
+/// impl Reporter for Syslog {
+///     fn render(&self, error: &MainError, f: &mut dyn fmt::Write) -> fmt::Result {
+///         write!(f, "<3>{error}")
+///     }
+/// }
+/// let error = MainError::from(std::io::Error::other("disk on fire"));
+/// set_reporter(Some(Box::new(Syslog)));
+/// assert_eq!(format!("{error:?}"), "<3>disk on fire");
+/// let style = std::env::var("MY_TOOL_ERROR_FORMAT").unwrap_or("json".to_string());
+/// set_reporter(Style::from_name(&style).map(|style| Box::new(style) as Box<dyn Reporter>));
+/// assert_eq!(format!("{error:?}"), r#"{"message":"disk on fire","causes":[]}"#);
+/// ```
+pub trait Reporter: Send + Sync {
+    /// write the report of `error`
+    fn render(&self, error: &MainError, f: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+/// Renders with the default [`MainErrorOptions`] in this style
+impl Reporter for Style {
+    fn render(&self, error: &MainError, f: &mut dyn fmt::Write) -> fmt::Result {
+        Reporter::render(&MainErrorOptions::new().style(*self), error, f)
+    }
+}
+
+impl Reporter for MainErrorOptions {
+    fn render(&self, error: &MainError, f: &mut dyn fmt::Write) -> fmt::Result {
+        MainErrorOptions::render(self, &error.to_report(), f)
+    }
+}
+
+/// shared, so reports render without holding the lock and reporters may format other errors or call [`set_reporter`]
+static REPORTER: RwLock<Option<Arc<dyn Reporter>>> = RwLock::new(None);
+
+/// # Select the process wide [`Reporter`], `None` restores the default [`MainErrorOptions`]
+/// Call this once at startup.
+pub fn set_reporter(reporter: Option<Box<dyn Reporter>>) {
+    *REPORTER.write().unwrap_or_else(|e| e.into_inner()) = reporter.map(Arc::from);
+}
+
+/// # The report style of a [`MainError`] at the type level
+/// It renders the `Debug` output of `MainError<Self>`, so a library can fix the style of its errors
+/// without the process wide [`set_reporter`] and [`crate::set_error_style`] state. The default [`ConfiguredReport`] follows that state.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{CompactReport, ContextError, MainError};
+/// fn export() -> Result<(), MainError<CompactReport>> {
+///     Err(ContextError::new("exporting failed", std::io::Error::other("disk on fire")))?
+/// }
+/// assert_eq!(format!("{:?}", export().unwrap_err()), "exporting failed: disk on fire");
+/// ```
+pub trait ReportStyle: Sized {
+    /// write the report of `error`
+    fn render(error: &MainError<Self>, f: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+/// The [`ReportStyle`] following the process wide [`set_reporter`] and [`crate::set_error_style`] selection, the default
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConfiguredReport;
+
+impl ReportStyle for ConfiguredReport {
+    fn render(error: &MainError, f: &mut dyn fmt::Write) -> fmt::Result {
+        let reporter = REPORTER.read().unwrap_or_else(|e| e.into_inner()).clone();
+        match reporter.as_deref() {
+            Some(reporter) => reporter.render(error, f),
+            None => Reporter::render(&MainErrorOptions::default(), error, f),
+        }
+    }
+}
+
+macro_rules! report_styles {
+    ($($(#[$doc:meta])* $name:ident => $style:expr,)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+            pub struct $name;
+
+            impl ReportStyle for $name {
+                fn render(error: &MainError<Self>, f: &mut dyn fmt::Write) -> fmt::Result {
+                    MainErrorOptions::new()
+                        .style($style)
+                        .render(&error.to_report(), f)
+                }
+            }
+        )*
+    };
+}
+
+report_styles! {
+    /// The [`ReportStyle`] of [`Style::Plain`]
+    PlainReport => Style::Plain,
+    /// The [`ReportStyle`] of [`Style::Compact`]
+    CompactReport => Style::Compact,
+    /// The [`ReportStyle`] of [`Style::Fancy`]
+    FancyReport => Style::Fancy,
+    /// The [`ReportStyle`] of [`Style::Json`]
+    JsonReport => Style::Json,
+    /// The [`ReportStyle`] of [`Style::Numbered`]
+    NumberedReport => Style::Numbered,
+    /// The [`ReportStyle`] of [`Style::EndUser`]
+    EndUserReport => Style::EndUser,
+}
@@ -0,0 +1,55 @@
+use crate::{canonical, FatIOError};
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// # Combinators attaching a path to the io error of a `Result`
+/// They cover the gaps between a raw `map_err` and the wrapper functions, e.g. for io done through methods of an open file.
+/// The path is recorded like in [`crate::fat_io_wrap_std`], the results convert into a [`crate::MainError`] with `?`.
+/// # Examples
+/// ```rust
+/// use std::io::ErrorKind;
+/// use vg_errortools::FatResultExt;
+/// let path = "does/not/exist.txt";
+/// let error = std::fs::File::open(path).map_fat(path).unwrap_err();
+/// assert_eq!(error.classify(), (ErrorKind::NotFound, std::path::Path::new(path)));
+/// let settings = std::fs::read_to_string(path).or_fat_default(path).unwrap();
+/// assert_eq!(settings, "");
+/// ```
+pub trait FatResultExt<T> {
+    /// wrap the io error into a [`FatIOError`] for `path`
+    fn map_fat(self, path: impl AsRef<Path>) -> Result<T, FatIOError>;
+
+    /// the default value if the file was not found, other io errors are wrapped into a [`FatIOError`] for `path`
+    fn or_fat_default(self, path: impl AsRef<Path>) -> Result<T, FatIOError>
+    where
+        T: Default;
+
+    /// # The value, panicking with `message` and the [`FatIOError`] for `path` otherwise
+    /// # Panics
+    /// If the result is an error.
+    fn fat_expect(self, path: impl AsRef<Path>, message: &str) -> T;
+}
+
+impl<T> FatResultExt<T> for std::io::Result<T> {
+    fn map_fat(self, path: impl AsRef<Path>) -> Result<T, FatIOError> {
+        self.map_err(|e| FatIOError::from_shared_path(e, canonical::recorded_path(path.as_ref())))
+    }
+
+    fn or_fat_default(self, path: impl AsRef<Path>) -> Result<T, FatIOError>
+    where
+        T: Default,
+    {
+        match self {
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(T::default()),
+            result => result.map_fat(path),
+        }
+    }
+
+    #[track_caller]
+    fn fat_expect(self, path: impl AsRef<Path>, message: &str) -> T {
+        match self.map_fat(path) {
+            Ok(value) => value,
+            Err(e) => panic!("{message}: {e}"),
+        }
+    }
+}
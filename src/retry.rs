@@ -0,0 +1,118 @@
+use crate::{canonical, capture, FatIOError};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::Duration;
+
+/// How often [`fat_io_wrap_std_retry_interrupted`] retries an interrupted call before reporting it
+pub const INTERRUPTED_RETRIES: usize = 16;
+
+/// # Wrapper for std::io functions retrying calls interrupted by signals
+/// Same as [`crate::fat_io_wrap_std`], but `f` is called again while it fails with `ErrorKind::Interrupted` (`EINTR`),
+/// up to [`INTERRUPTED_RETRIES`] times. Processes receiving many signals, e.g. from profilers or child processes, otherwise fail at random.
+/// `f` must be safe to repeat, which holds for opening, reading or writing whole files.
+/// # Examples
+/// ```rust
+/// use std::cell::Cell;
+/// use std::io::ErrorKind;
+/// use vg_errortools::{fat_io_wrap_std_retry_interrupted, INTERRUPTED_RETRIES};
+/// let calls = Cell::new(0);
+/// let flaky = |path: &str| {
+///     calls.set(calls.get() + 1);
+///     match calls.get() {
+///         1..=2 => Err(ErrorKind::Interrupted.into()),
+///         _ => std::fs::read_to_string(path),
+///     }
+/// };
+/// assert!(fat_io_wrap_std_retry_interrupted("Cargo.toml", &flaky).is_ok());
+/// assert_eq!(calls.get(), 3);
+/// let error = fat_io_wrap_std_retry_interrupted("Cargo.toml", &|_| Err::<(), _>(ErrorKind::Interrupted.into())).unwrap_err();
+/// assert_eq!(error.kind(), ErrorKind::Interrupted);
+/// ```
+pub fn fat_io_wrap_std_retry_interrupted<'a, T, P: AsRef<Path> + ?Sized>(
+    path: &'a P,
+    f: &dyn Fn(&'a P) -> std::io::Result<T>,
+) -> Result<T, FatIOError> {
+    let started = capture::start_timer();
+    let mut result = f(path);
+    for _ in 0..INTERRUPTED_RETRIES {
+        match &result {
+            Err(e) if e.kind() == ErrorKind::Interrupted => result = f(path),
+            _ => break,
+        }
+    }
+    result.map_err(|e| {
+        FatIOError::from_shared_path(e, canonical::recorded_path(path.as_ref()))
+            .with_elapsed(started)
+    })
+}
+
+/// # What [`fat_io_wrap_std_would_block`] does when a call fails with `ErrorKind::WouldBlock`
+/// Non-blocking descriptors and lock attempts report `WouldBlock` instead of waiting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WouldBlockStrategy {
+    /// report the `WouldBlock` error unchanged, the default
+    #[default]
+    PassThrough,
+    /// report it as `ErrorKind::TimedOut` error, with the original error as source, so it is told apart from real failures
+    TimedOut,
+    /// call again after `backoff`, doubling the wait after every attempt, and report `TimedOut` after `attempts` retries
+    Retry {
+        /// the number of retries
+        attempts: u32,
+        /// the wait before the first retry
+        backoff: Duration,
+    },
+}
+
+/// # Wrapper for std::io functions on non-blocking files and locks
+/// Same as [`crate::fat_io_wrap_std`], but `WouldBlock` results are handled according to `strategy`.
+/// # Examples
+/// ```rust
+/// use std::cell::Cell;
+/// use std::io::ErrorKind;
+/// use std::time::Duration;
+/// use vg_errortools::{fat_io_wrap_std_would_block, WouldBlockStrategy};
+/// let calls = Cell::new(0);
+/// let busy = |_: &str| {
+///     calls.set(calls.get() + 1);
+///     Err::<(), _>(ErrorKind::WouldBlock.into())
+/// };
+/// let retry = WouldBlockStrategy::Retry { attempts: 2, backoff: Duration::from_millis(1) };
+/// let error = fat_io_wrap_std_would_block("scan.lock", retry, &busy).unwrap_err();
+/// assert_eq!((error.kind(), calls.get()), (ErrorKind::TimedOut, 3));
+/// let error = fat_io_wrap_std_would_block("scan.lock", WouldBlockStrategy::PassThrough, &busy).unwrap_err();
+/// assert_eq!(error.kind(), ErrorKind::WouldBlock);
+/// ```
+pub fn fat_io_wrap_std_would_block<'a, T, P: AsRef<Path> + ?Sized>(
+    path: &'a P,
+    strategy: WouldBlockStrategy,
+    f: &dyn Fn(&'a P) -> std::io::Result<T>,
+) -> Result<T, FatIOError> {
+    let started = capture::start_timer();
+    let mut result = f(path);
+    if let WouldBlockStrategy::Retry {
+        attempts,
+        mut backoff,
+    } = strategy
+    {
+        for _ in 0..attempts {
+            match &result {
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    std::thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2);
+                    result = f(path);
+                }
+                _ => break,
+            }
+        }
+    }
+    result.map_err(|e| {
+        let give_up = strategy != WouldBlockStrategy::PassThrough;
+        let e = match e.kind() {
+            ErrorKind::WouldBlock if give_up => std::io::Error::new(ErrorKind::TimedOut, e),
+            _ => e,
+        };
+        FatIOError::from_shared_path(e, canonical::recorded_path(path.as_ref()))
+            .with_elapsed(started)
+    })
+}
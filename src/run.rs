@@ -0,0 +1,96 @@
+use crate::{sink, MainError, ReportStyle};
+use std::error::Error;
+use std::io::Write;
+
+impl<R> MainError<R> {
+    /// # The exit code of a process ending with this error
+    /// The code set by [`crate::set_broken_pipe_exit_code`] for closed output, `130` for interruptions of `run_with_signals` and `1` otherwise.
+    /// # Examples
+    /// ```rust
+    /// use vg_errortools::{set_broken_pipe_exit_code, MainError};
+    /// let error = MainError::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+    /// assert_eq!(error.exit_code(), 1);
+    /// set_broken_pipe_exit_code(Some(0));
+    /// assert_eq!(error.exit_code(), 0);
+    /// ```
+    pub fn exit_code(&self) -> i32 {
+        #[cfg(feature = "signals")]
+        if crate::chain(self.error()).any(|e| e.is::<crate::Interrupted>()) {
+            return crate::INTERRUPTED_EXIT_CODE;
+        }
+        self.broken_pipe_exit().unwrap_or(1)
+    }
+}
+
+impl<R: ReportStyle> MainError<R> {
+    /// # Report this error like `main` would, without the `Error: ` prefix, and exit with [`MainError::exit_code`]
+    /// The exit path of [`run_main`], for errors ending the program elsewhere, e.g. the [`crate::Interrupted`] error of `run_with_signals`.
+    /// The report is written to stderr in the `Debug` format, the [`crate::on_error`] callbacks run,
+    /// the report is written to the [`crate::add_report_sink`] sinks and a crash report is written if [`crate::set_crash_report_dir`] is set.
+    /// Broken pipes exit quietly if [`crate::set_broken_pipe_exit_code`] is set. Then the output is flushed and the process exits.
+    pub fn exit(self) -> ! {
+        let code = self.exit_code();
+        if self.broken_pipe_exit().is_none() {
+            self.notify_once();
+            self.write_configured_crash_report();
+            let text = format!("{self:?}\n");
+            sink::tee(&text);
+            let _ = std::io::stderr().lock().write_all(text.as_bytes());
+        }
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stderr().flush();
+        std::process::exit(code)
+    }
+}
+
+/// # Run the body of `main` and exit with the outcome
+/// A failure of `f` is converted into a [`MainError`] and reported with [`MainError::exit`], which exits with [`MainError::exit_code`].
+/// On success the output is flushed and the process exits with `0`.
+/// # Examples
+/// ```rust, no_run
+/// use vg_errortools::run_main;
+/// fn main() {
+///     run_main(|| {
+///         let config = std::fs::read_to_string("config.toml")?;
+///         println!("{config}");
+///         Ok::<_, std::io::Error>(())
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn run_main<E: Into<Box<dyn Error>>>(f: impl FnOnce() -> Result<(), E>) -> ! {
+    match f() {
+        Ok(()) => {
+            let _ = std::io::stdout().flush();
+            std::process::exit(0)
+        }
+        Err(error) => MainError::from(error).exit(),
+    }
+}
+
+/// # Run an async main on a current-thread tokio runtime and exit with the outcome, see [`run_main`]
+/// For a multi-threaded runtime, build it yourself and call `run_main(|| runtime.block_on(main))`.
+/// # Examples
+/// ```rust, no_run
+/// use vg_errortools::{fat_io_wrap_tokio, run_main_async};
+/// fn main() {
+///     run_main_async(async {
+///         let config = fat_io_wrap_tokio("config.toml", tokio::fs::read_to_string).await?;
+///         println!("{config}");
+///         Ok::<_, vg_errortools::FatIOError>(())
+///     })
+/// }
+/// ```
+#[cfg(feature = "tokio")]
+#[track_caller]
+pub fn run_main_async<E: Into<Box<dyn Error>>>(
+    main: impl std::future::Future<Output = Result<(), E>>,
+) -> ! {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    match runtime {
+        Ok(runtime) => run_main(|| runtime.block_on(main)),
+        Err(e) => MainError::from(e).exit(),
+    }
+}
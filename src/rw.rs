@@ -0,0 +1,118 @@
+use crate::{canonical, FatIOError};
+use std::io::{BufRead, IoSlice, IoSliceMut, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// # A `Read` adaptor embedding a path into every error
+/// Errors of the inner reader are returned as io errors of the same kind carrying a [`FatIOError`] for the path,
+/// so generic code taking `impl Read` produces fat errors, recoverable with [`FatIOError::from_io_error_payload`].
+/// # Examples
+/// ```rust
+/// use std::io::Read;
+/// use vg_errortools::{FatIOError, FatReader};
+/// let path = std::env::temp_dir().join("vg_errortools_reader_doc.raw");
+/// let write_only = std::fs::File::create(&path).unwrap();
+/// let mut reader = FatReader::new(write_only, &path);
+/// let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+/// let fat = FatIOError::from_io_error_payload(&error).unwrap();
+/// assert_eq!(fat.path(), path);
+/// ```
+#[derive(Debug)]
+pub struct FatReader<R> {
+    inner: R,
+    path: Arc<Path>,
+}
+
+impl<R> FatReader<R> {
+    /// wrap `inner`, embedding `path` into its errors
+    pub fn new(inner: R, path: impl AsRef<Path>) -> Self {
+        FatReader {
+            inner,
+            path: canonical::recorded_path(path.as_ref()),
+        }
+    }
+
+    /// the embedded path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// unwrap the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for FatReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner
+            .read(buf)
+            .map_err(|e| FatIOError::embed(e, &self.path))
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> std::io::Result<usize> {
+        self.inner
+            .read_vectored(bufs)
+            .map_err(|e| FatIOError::embed(e, &self.path))
+    }
+}
+
+impl<R: BufRead> BufRead for FatReader<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner
+            .fill_buf()
+            .map_err(|e| FatIOError::embed(e, &self.path))
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount)
+    }
+}
+
+/// # A `Write` adaptor embedding a path into every error
+/// The writing counterpart of [`FatReader`].
+#[derive(Debug)]
+pub struct FatWriter<W> {
+    inner: W,
+    path: Arc<Path>,
+}
+
+impl<W> FatWriter<W> {
+    /// wrap `inner`, embedding `path` into its errors
+    pub fn new(inner: W, path: impl AsRef<Path>) -> Self {
+        FatWriter {
+            inner,
+            path: canonical::recorded_path(path.as_ref()),
+        }
+    }
+
+    /// the embedded path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// unwrap the inner writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for FatWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner
+            .write(buf)
+            .map_err(|e| FatIOError::embed(e, &self.path))
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> std::io::Result<usize> {
+        self.inner
+            .write_vectored(bufs)
+            .map_err(|e| FatIOError::embed(e, &self.path))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner
+            .flush()
+            .map_err(|e| FatIOError::embed(e, &self.path))
+    }
+}
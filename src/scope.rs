@@ -0,0 +1,75 @@
+use crate::MainError;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::error::Error;
+use std::marker::PhantomData;
+
+thread_local! {
+    static SCOPES: RefCell<Vec<Cow<'static, str>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// # A guard naming what the current thread is doing while it is alive
+/// [`MainError`]s created while scopes are active record them, outermost first, and reports show them in a `while:` line.
+/// This attaches context to errors created deep inside code which knows nothing about it.
+/// Errors usually become a `MainError` only in `main`, after the scopes they escaped are gone, so convert them inside the scope with [`ScopeResultExt::scoped`].
+/// # Examples
+/// ```rust
+/// use vg_errortools::{ErrorScope, MainError, MainErrorOptions, ScopeResultExt};
+/// fn import(project: &str) -> Result<(), MainError> {
+///     let _scope = ErrorScope::new(format!("importing project {project}"));
+///     let _meshes = ErrorScope::new("loading meshes");
+///     std::fs::read("does/not/exist.stl").scoped()?;
+///     Ok(())
+/// }
+/// let error = import("turbine").unwrap_err();
+/// assert_eq!(error.scopes(), ["importing project turbine", "loading meshes"]);
+/// assert!(error
+///     .render(&MainErrorOptions::new())
+///     .ends_with("\nwhile: importing project turbine > loading meshes"));
+/// ```
+#[must_use = "the scope ends when the guard is dropped"]
+pub struct ErrorScope {
+    depth: usize,
+    not_send: PhantomData<*const ()>,
+}
+
+impl ErrorScope {
+    /// enter a scope named `label` on the current thread until the guard is dropped
+    pub fn new(label: impl Into<Cow<'static, str>>) -> Self {
+        let depth = SCOPES.with_borrow_mut(|scopes| {
+            scopes.push(label.into());
+            scopes.len() - 1
+        });
+        ErrorScope {
+            depth,
+            not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for ErrorScope {
+    fn drop(&mut self) {
+        SCOPES.with_borrow_mut(|scopes| scopes.truncate(self.depth));
+    }
+}
+
+/// the labels of the active scopes of the current thread, outermost first
+pub(crate) fn current() -> Vec<Cow<'static, str>> {
+    SCOPES.with_borrow(Clone::clone)
+}
+
+/// # Convert errors into a [`MainError`] while the [`ErrorScope`]s they occur in are still active
+pub trait ScopeResultExt<T> {
+    /// convert the error into a [`MainError`] recording the active scopes
+    fn scoped(self) -> Result<T, MainError>;
+}
+
+impl<T, E: Into<Box<dyn Error>>> ScopeResultExt<T> for Result<T, E> {
+    #[track_caller]
+    fn scoped(self) -> Result<T, MainError> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(MainError::from(error)),
+        }
+    }
+}
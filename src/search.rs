@@ -0,0 +1,123 @@
+use crate::template::display_path;
+use crate::FatIOError;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// # All candidate locations which were tried, each with the reason it failed
+/// Created by [`load_first_existing`] if no candidate could be loaded, or by custom search logic like `PATH` lookups,
+/// plugin discovery or asset search paths with [`FatSearchError::new`] and [`FatSearchError::attempt`].
+/// # Examples
+/// ```rust
+/// use std::io::ErrorKind;
+/// use vg_errortools::FatSearchError;
+/// let mut error = FatSearchError::new("plugin 'denoise' not found");
+/// for dir in ["plugins", "/opt/vg/plugins"] {
+///     error.attempt(format!("{dir}/denoise.so"), std::io::Error::from(ErrorKind::NotFound));
+/// }
+/// assert_eq!(error.kind(), ErrorKind::NotFound);
+/// assert_eq!(
+///     error.to_string(),
+///     "plugin 'denoise' not found, tried the following locations:\n    \
+///     'plugins/denoise.so': entity not found\n    \
+///     '/opt/vg/plugins/denoise.so': entity not found"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct FatSearchError {
+    message: String,
+    attempts: Vec<(PathBuf, Box<dyn Error + Send + Sync>)>,
+}
+
+impl FatSearchError {
+    /// create an error without attempts, `message` leads the list of tried locations
+    pub fn new(message: impl Into<String>) -> Self {
+        FatSearchError {
+            message: message.into(),
+            attempts: Vec::new(),
+        }
+    }
+
+    /// record a tried location with the reason it failed
+    pub fn attempt(
+        &mut self,
+        path: impl Into<PathBuf>,
+        reason: impl Into<Box<dyn Error + Send + Sync>>,
+    ) -> &mut Self {
+        self.attempts.push((path.into(), reason.into()));
+        self
+    }
+
+    /// the common io error kind of all attempts, `NotFound` if there are none and `Other` if the attempts differ or are no io errors
+    pub fn kind(&self) -> std::io::ErrorKind {
+        let mut kinds = self.attempts.iter().map(|(_, reason)| {
+            reason
+                .downcast_ref::<std::io::Error>()
+                .map(std::io::Error::kind)
+                .or_else(|| reason.downcast_ref::<FatIOError>().map(FatIOError::kind))
+        });
+        match kinds.next() {
+            None => std::io::ErrorKind::NotFound,
+            Some(Some(first)) if kinds.all(|kind| kind == Some(first)) => first,
+            Some(_) => std::io::ErrorKind::Other,
+        }
+    }
+
+    /// the attempted paths in the order they were tried, each with its reason
+    pub fn attempts(&self) -> impl Iterator<Item = (&Path, &(dyn Error + Send + Sync + 'static))> {
+        self.attempts
+            .iter()
+            .map(|(path, reason)| (path.as_path(), reason.as_ref()))
+    }
+}
+
+impl Display for FatSearchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.attempts.is_empty() {
+            return write!(f, "{}, no locations to try", self.message);
+        }
+        write!(f, "{}, tried the following locations:", self.message)?;
+        for (path, reason) in &self.attempts {
+            write!(f, "\n    '{}': {reason}", display_path(path))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for FatSearchError {}
+
+/// keeps the [`FatSearchError::kind`]
+impl From<FatSearchError> for std::io::Error {
+    fn from(e: FatSearchError) -> Self {
+        std::io::Error::new(e.kind(), e)
+    }
+}
+
+/// # Load the first candidate that works, listing every attempted location otherwise
+/// The candidates are tried in order with `loader` until one succeeds. If all fail, the error holds each path with its reason,
+/// so "config not found" always shows where was looked.
+/// # Examples
+/// ```rust
+/// use vg_errortools::load_first_existing;
+/// let candidates = ["does/not/exist.toml", "neither/does/this.toml"];
+/// let error = load_first_existing(candidates, |path| std::fs::read_to_string(path)).unwrap_err();
+/// assert_eq!(error.attempts().count(), 2);
+/// assert!(error.to_string().starts_with("loading failed, tried the following locations:\n    'does/not/exist.toml': "));
+/// ```
+pub fn load_first_existing<T, E: Into<Box<dyn Error + Send + Sync>>, P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+    mut loader: impl FnMut(&Path) -> Result<T, E>,
+) -> Result<T, FatSearchError> {
+    let mut error = FatSearchError::new("loading failed");
+    for path in paths {
+        let path = path.as_ref();
+        match loader(path) {
+            Ok(loaded) => return Ok(loaded),
+            Err(e) => {
+                error.attempt(path, e);
+            }
+        }
+    }
+    Err(error)
+}
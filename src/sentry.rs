@@ -0,0 +1,76 @@
+use crate::{chain, FatIOError, MainError};
+use sentry_core::protocol::{Event, Value};
+use std::backtrace::BacktraceStatus;
+use std::time::Duration;
+
+/// # Convert a [`MainError`] into a Sentry event
+/// Every error of the chain becomes one exception of the event, stacked with the root cause first as Sentry expects.
+/// The paths of all [`FatIOError`]s in the chain are attached as `file.paths` extra, the first one also as `file.path` tag,
+/// the correlation ID as `correlation_id` tag, a captured creation time as event timestamp and a captured backtrace as `backtrace` extra.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{sentry_event, FatIOError, MainError};
+/// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "my_file.txt".into());
+/// let error: MainError = fat.into();
+/// let event = sentry_event(&error);
+/// assert_eq!(event.exception.len(), 2);
+/// assert_eq!(event.tags["file.path"], "my_file.txt");
+/// ```
+pub fn sentry_event<R>(error: &MainError<R>) -> Event<'static> {
+    let mut event = sentry_core::event_from_error(error.error());
+    let paths: Vec<String> = chain(error.error())
+        .filter_map(|e| e.downcast_ref::<FatIOError>())
+        .map(|fat| fat.path().to_string_lossy().into_owned())
+        .collect();
+    if let Some(first) = paths.first() {
+        event.tags.insert("file.path".to_string(), first.clone());
+    }
+    if !paths.is_empty() {
+        let paths = paths.into_iter().map(Value::from).collect();
+        event
+            .extra
+            .insert("file.paths".to_string(), Value::Array(paths));
+    }
+    if let Some(id) = error.correlation_id() {
+        event
+            .tags
+            .insert("correlation_id".to_string(), id.to_string());
+    }
+    if let Some(timestamp) = error.timestamp() {
+        event.timestamp = timestamp;
+    }
+    if error.backtrace().status() == BacktraceStatus::Captured {
+        event.extra.insert(
+            "backtrace".to_string(),
+            Value::from(error.backtrace().to_string()),
+        );
+    }
+    event
+}
+
+/// # Run a main function and report its error to Sentry on exit
+/// If `f` fails, the error is captured with [`sentry_event`] on the current hub and the client is flushed for up to `flush_timeout`,
+/// then the error is returned unchanged to be printed as usual.
+/// # Examples
+/// ```rust, no_run
+/// use std::time::Duration;
+/// use vg_errortools::{with_sentry_report, MainError};
+/// fn main() -> Result<(), MainError> {
+///     with_sentry_report(Duration::from_secs(2), || {
+///         std::fs::read_to_string("config.toml")?;
+///         Ok(())
+///     })
+/// }
+/// ```
+pub fn with_sentry_report<R>(
+    flush_timeout: Duration,
+    f: impl FnOnce() -> Result<(), MainError<R>>,
+) -> Result<(), MainError<R>> {
+    f().inspect_err(|error| {
+        let hub = sentry_core::Hub::current();
+        hub.capture_event(sentry_event(error));
+        if let Some(client) = hub.client() {
+            client.flush(Some(flush_timeout));
+        }
+    })
+}
@@ -0,0 +1,150 @@
+use crate::{capture_backtrace, scope, ErrorScope, MainError, ThreadInfo};
+use std::backtrace::Backtrace;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::panic::{AssertUnwindSafe, Location};
+use std::sync::{mpsc, Mutex, OnceLock};
+
+/// The exit code of a program stopped by [`run_with_signals`], the code shells report for `SIGINT`
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// # The error reported when [`run_with_signals`] stops the program on Ctrl-C or a termination request
+/// # Examples
+/// ```rust
+/// use vg_errortools::{Interrupted, MainError};
+/// let error = MainError::from(Interrupted);
+/// assert_eq!(error.to_string(), "interrupted by Ctrl-C or a termination request");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("interrupted by Ctrl-C or a termination request")
+    }
+}
+
+impl Error for Interrupted {}
+
+static HANDLER: OnceLock<Result<(), String>> = OnceLock::new();
+
+/// wakes up the running [`run_with_signals`], if any
+static WAKE: Mutex<Option<Box<dyn Fn() + Send>>> = Mutex::new(None);
+
+fn on_signal() {
+    match WAKE.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(wake) => wake(),
+        None => std::process::exit(INTERRUPTED_EXIT_CODE),
+    }
+}
+
+/// the error of the body of [`run_with_signals`] with what a [`MainError`] records about the thread it occurred on
+struct Failure {
+    error: Box<dyn Error + Send + Sync>,
+    backtrace: Backtrace,
+    thread: Option<ThreadInfo>,
+    scopes: Vec<Cow<'static, str>>,
+}
+
+impl Failure {
+    fn capture(error: Box<dyn Error + Send + Sync>) -> Self {
+        Failure {
+            error,
+            backtrace: capture_backtrace(),
+            thread: ThreadInfo::capture(),
+            scopes: scope::current(),
+        }
+    }
+}
+
+/// what the body of [`run_with_signals`] sends to the waiting caller
+enum Outcome<T> {
+    Finished(std::thread::Result<Result<T, Failure>>),
+    Interrupted,
+}
+
+/// # Run a main function that is stopped cleanly by Ctrl-C, `SIGTERM` or `SIGHUP`
+/// `f` runs on a separate thread while the calling thread waits for it or for a signal.
+/// On a signal an [`Interrupted`] error is returned right away and the still running `f` is abandoned,
+/// so the report is written on the normal path, not concurrently with it. Otherwise the result of `f` is returned as usual and a panic of `f` is resumed.
+/// The [`ErrorScope`]s of the caller stay active in `f`, and an error of `f` records the backtrace and thread of `f`, like converting it inside `f` would.
+/// Exit with [`MainError::exit`] to end the process with [`INTERRUPTED_EXIT_CODE`] for interruptions.
+/// Signals outside of `run_with_signals` exit with [`INTERRUPTED_EXIT_CODE`] right away.
+/// Async main functions run through their runtime, e.g. `run_with_signals(move || runtime.block_on(run()))`.
+/// Fails if another Ctrl-C handler is installed already.
+/// # Examples
+/// ```rust
+/// use vg_errortools::run_with_signals;
+/// assert_eq!(run_with_signals(|| "42".parse::<u8>()).unwrap(), 42);
+/// let result = run_with_signals(|| std::fs::write("", "settings"));
+/// assert!(result.unwrap_err().error().is::<std::io::Error>());
+/// ```
+///
+/// ```rust
+/// use vg_errortools::{run_with_signals, ErrorScope};
+/// let _scope = ErrorScope::new("loading settings");
+/// let error = run_with_signals(|| std::fs::read("does/not/exist.toml")).unwrap_err();
+/// assert_eq!(error.scopes(), ["loading settings"]);
+/// ```
+///
+/// ```rust, no_run
+/// use vg_errortools::run_with_signals;
+/// async fn serve() -> Result<(), std::io::Error> {
+///     Ok(())
+/// }
+/// fn main() {
+///     let runtime = tokio::runtime::Runtime::new().unwrap();
+///     if let Err(error) = run_with_signals(move || runtime.block_on(serve())) {
+///         error.exit()
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn run_with_signals<T, E>(
+    f: impl FnOnce() -> Result<T, E> + Send + 'static,
+) -> Result<T, MainError>
+where
+    T: Send + 'static,
+    E: Into<Box<dyn Error + Send + Sync>> + 'static,
+{
+    if let Err(e) = HANDLER.get_or_init(|| ctrlc::set_handler(on_signal).map_err(|e| e.to_string()))
+    {
+        return Err(MainError::from(format!(
+            "installing the signal handler failed: {e}"
+        )));
+    }
+    let caller = Location::caller();
+    let scopes = scope::current();
+    let (sender, receiver) = mpsc::channel();
+    let interrupt = sender.clone();
+    *WAKE.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(move || {
+        let _ = interrupt.send(Outcome::Interrupted);
+    }));
+    let body = std::thread::Builder::new()
+        .name("run_with_signals".to_string())
+        .spawn(move || {
+            let _scopes: Vec<ErrorScope> = scopes.into_iter().map(ErrorScope::new).collect();
+            let result = std::panic::catch_unwind(AssertUnwindSafe(f))
+                .map(|result| result.map_err(|error| Failure::capture(error.into())));
+            let _ = sender.send(Outcome::Finished(result));
+        })
+        .map(|_| receiver.recv().unwrap_or(Outcome::Interrupted));
+    *WAKE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    let outcome = match body {
+        Ok(outcome) => outcome,
+        Err(e) => return Err(MainError::from(e)),
+    };
+    match outcome {
+        Outcome::Finished(Ok(Ok(value))) => Ok(value),
+        Outcome::Finished(Ok(Err(failure))) => Err(MainError::recorded(
+            failure.error,
+            failure.backtrace,
+            failure.thread,
+            failure.scopes,
+            caller,
+        )),
+        Outcome::Finished(Err(panic)) => std::panic::resume_unwind(panic),
+        Outcome::Interrupted => Err(MainError::from(Interrupted)),
+    }
+}
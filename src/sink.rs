@@ -0,0 +1,143 @@
+use crate::MainError;
+use std::error::Error;
+use std::io::Write;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// a sink registered with [`add_report_sink`], locked on its own so the list is not locked while writing
+type SharedSink = Arc<Mutex<Box<dyn Write + Send>>>;
+
+static SINKS: Mutex<Vec<SharedSink>> = Mutex::new(Vec::new());
+
+/// a callback registered with [`on_error`]
+pub type ErrorCallback = Box<dyn Fn(&(dyn Error + 'static)) + Send + Sync>;
+
+/// a callback registered with [`on_error`], shared so callbacks run without holding the lock
+type SharedCallback = Arc<dyn Fn(&(dyn Error + 'static)) + Send + Sync>;
+
+static ERROR_CALLBACKS: RwLock<Vec<SharedCallback>> = RwLock::new(Vec::new());
+
+/// # Register an additional sink for every [`crate::MainError`] report
+/// Reports written with [`crate::MainError::report`], the report printed by [`crate::run_main`] and panics reported by [`crate::install_panic_hook`] are also written to `sink`,
+/// e.g. a log file, a socket or a [`ReportBuffer`]. Write errors of sinks are ignored.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{add_report_sink, MainError, MainErrorOptions, ReportBuffer};
+/// let buffer = ReportBuffer::new();
+/// add_report_sink(Box::new(buffer.clone()));
+/// let error = MainError::from(std::io::Error::other("disk on fire"));
+/// error.report(&MainErrorOptions::new()).unwrap();
+/// assert_eq!(buffer.contents(), "disk on fire\n");
+/// ```
+pub fn add_report_sink(sink: Box<dyn Write + Send>) {
+    SINKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::new(Mutex::new(sink)));
+}
+
+/// remove all sinks registered with [`add_report_sink`]
+pub fn clear_report_sinks() {
+    SINKS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// write a report to all registered sinks
+pub(crate) fn tee(text: &str) {
+    let sinks = SINKS.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    for sink in sinks {
+        let mut sink = sink.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = sink.write_all(text.as_bytes()).and_then(|_| sink.flush());
+    }
+}
+
+/// # Register a callback invoked whenever a [`crate::MainError`] is about to be reported
+/// It runs once per error, before the first report of [`crate::MainError::report`] or [`crate::run_main`] is written, and receives the wrapped error,
+/// whose causes are available through `source`. Formatting an error with `{:?}` does not invoke callbacks. Use it for metrics, cleanup or notifying the user.
+/// # Examples
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use vg_errortools::{on_error, MainError, MainErrorOptions};
+/// static FAILURES: AtomicUsize = AtomicUsize::new(0);
+/// on_error(Box::new(|error| {
+///     assert_eq!(error.to_string(), "disk on fire");
+///     FAILURES.fetch_add(1, Ordering::Relaxed);
+/// }));
+/// let error = MainError::from(std::io::Error::other("disk on fire"));
+/// error.report(&MainErrorOptions::new()).unwrap();
+/// assert_eq!(FAILURES.load(Ordering::Relaxed), 1);
+/// ```
+pub fn on_error(callback: ErrorCallback) {
+    ERROR_CALLBACKS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::from(callback));
+}
+
+/// remove all callbacks registered with [`on_error`]
+pub fn clear_error_callbacks() {
+    ERROR_CALLBACKS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
+}
+
+/// invoke all registered callbacks with `error`
+pub(crate) fn notify(error: &(dyn Error + 'static)) {
+    let callbacks = ERROR_CALLBACKS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    for callback in callbacks {
+        callback(error);
+    }
+}
+
+impl<R> MainError<R> {
+    /// invoke the callbacks unless they were invoked for this error already
+    pub(crate) fn notify_once(&self) {
+        if !self.inner.notified.swap(true, Ordering::Relaxed) {
+            notify(self.error());
+        }
+    }
+}
+
+/// # An in-memory report sink
+/// Clones share the same buffer, so one clone can be registered with [`add_report_sink`] and another one read.
+#[derive(Clone, Debug, Default)]
+pub struct ReportBuffer {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl ReportBuffer {
+    /// an empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// everything written so far
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buffer.lock().unwrap_or_else(|e| e.into_inner())).into_owned()
+    }
+
+    /// remove everything written so far
+    pub fn clear(&self) {
+        self.buffer
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl Write for ReportBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
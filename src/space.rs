@@ -0,0 +1,160 @@
+use crate::canonical;
+use crate::limit::HumanSize;
+use crate::template::display_path;
+use fs4::fs_std::FileExt;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// # A failed free-space query or preallocation with the path and the requested size
+/// Created by [`fat_available_space`], [`fat_allocate`] and [`check_space_for`], the source is the io error.
+#[derive(Debug)]
+pub struct FatSpaceError {
+    path: PathBuf,
+    requested: Option<u64>,
+    available: Option<u64>,
+    source: std::io::Error,
+}
+
+impl FatSpaceError {
+    /// the path queried or allocated
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// the requested number of bytes, `None` for plain queries
+    pub fn requested(&self) -> Option<u64> {
+        self.requested
+    }
+
+    /// the available number of bytes, if they could be queried
+    pub fn available(&self) -> Option<u64> {
+        self.available
+    }
+
+    /// the kind of the io error, `StorageFull` if there is not enough space
+    pub fn kind(&self) -> ErrorKind {
+        self.source.kind()
+    }
+}
+
+impl Display for FatSpaceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let path = display_path(&self.path);
+        match (self.requested, self.available) {
+            (Some(requested), Some(available)) if requested > available => write!(
+                f,
+                "not enough space for {} at '{path}', only {} available",
+                HumanSize(requested),
+                HumanSize(available)
+            ),
+            (Some(requested), _) => write!(
+                f,
+                "reserving {} at '{path}' failed with error {}",
+                HumanSize(requested),
+                self.source
+            ),
+            (None, _) => write!(
+                f,
+                "querying the free space at '{path}' failed with error {}",
+                self.source
+            ),
+        }
+    }
+}
+
+impl Error for FatSpaceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// keeps the kind
+impl From<FatSpaceError> for std::io::Error {
+    fn from(e: FatSpaceError) -> Self {
+        std::io::Error::new(e.kind(), e)
+    }
+}
+
+/// the path itself or its closest existing ancestor, files to be created are queried on their future file system
+fn existing_ancestor(path: &Path) -> &Path {
+    path.ancestors()
+        .find(|ancestor| !ancestor.as_os_str().is_empty() && ancestor.exists())
+        .unwrap_or(Path::new("."))
+}
+
+/// # The number of bytes available to the current user on the file system of `path`
+/// `path` does not need to exist yet, the file system of its closest existing ancestor is queried.
+pub fn fat_available_space(path: impl AsRef<Path>) -> Result<u64, FatSpaceError> {
+    let path = path.as_ref();
+    fs4::available_space(existing_ancestor(path)).map_err(|e| FatSpaceError {
+        path: canonical::recorded_path(path).to_path_buf(),
+        requested: None,
+        available: None,
+        source: e,
+    })
+}
+
+/// # Check that `bytes` fit onto the file system of `path`, e.g. before a large export
+/// If not, fails with a [`FatSpaceError`] of kind `StorageFull` with the requested and the available size.
+/// `path` does not need to exist yet, the file system of its closest existing ancestor is queried.
+/// # Examples
+/// ```rust
+/// use vg_errortools::check_space_for;
+/// let export = std::env::temp_dir().join("vg_errortools_space_doc").join("export.raw");
+/// check_space_for(&export, 1024).unwrap();
+/// let error = check_space_for(&export, u64::MAX).unwrap_err();
+/// assert_eq!(error.kind(), std::io::ErrorKind::StorageFull);
+/// assert!(error.to_string().starts_with("not enough space for 16 EiB at"));
+/// ```
+pub fn check_space_for(path: impl AsRef<Path>, bytes: u64) -> Result<(), FatSpaceError> {
+    let path = path.as_ref();
+    let available = fat_available_space(path).map_err(|e| FatSpaceError {
+        requested: Some(bytes),
+        ..e
+    })?;
+    if bytes > available {
+        return Err(FatSpaceError {
+            path: canonical::recorded_path(path).to_path_buf(),
+            requested: Some(bytes),
+            available: Some(available),
+            source: std::io::Error::new(ErrorKind::StorageFull, "not enough space"),
+        });
+    }
+    Ok(())
+}
+
+/// # Open or create the file at `path` and preallocate `len` bytes for it
+/// The content is kept, the file only grows if it is shorter than `len`.
+/// If the allocation fails, the error also carries the available space, if it could be queried.
+/// # Examples
+/// ```rust
+/// use vg_errortools::fat_allocate;
+/// let path = std::env::temp_dir().join("vg_errortools_allocate_doc.raw");
+/// let file = fat_allocate(&path, 4096).unwrap();
+/// assert_eq!(file.metadata().unwrap().len(), 4096);
+/// ```
+pub fn fat_allocate(path: impl AsRef<Path>, len: u64) -> Result<File, FatSpaceError> {
+    let path = path.as_ref();
+    let error = |source| FatSpaceError {
+        path: canonical::recorded_path(path).to_path_buf(),
+        requested: Some(len),
+        available: None,
+        source,
+    };
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(error)?;
+    FileExt::allocate(&file, len).map_err(|e| FatSpaceError {
+        available: fs4::available_space(path).ok(),
+        ..error(e)
+    })?;
+    Ok(file)
+}
@@ -0,0 +1,33 @@
+use crate::ErrorContext;
+use futures_util::{Stream, TryStreamExt};
+
+/// # Attach a context to every item error of a fallible stream
+/// # Examples
+/// ```rust
+/// use futures_util::{stream, StreamExt};
+/// use std::path::PathBuf;
+/// use vg_errortools::FatStreamExt;
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let chunks = stream::iter([Ok(vec![1u8]), Err(std::io::Error::other("truncated chunk"))]);
+/// let mut chunks = std::pin::pin!(chunks.fat_context(PathBuf::from("volume_17.raw")));
+/// assert!(chunks.next().await.unwrap().is_ok());
+/// let error = chunks.next().await.unwrap().unwrap_err();
+/// assert_eq!(error.path(), PathBuf::from("volume_17.raw"));
+///
+/// let labelled = stream::iter([Err::<(), _>(std::fmt::Error)]).fat_context("decoding header");
+/// let error = std::pin::pin!(labelled).next().await.unwrap().unwrap_err();
+/// assert_eq!(error.label(), "decoding header");
+/// # }
+/// ```
+pub trait FatStreamExt<T, E>: Stream<Item = Result<T, E>> + Sized {
+    /// wrap each item error with `context`, a path for io errors or a static label
+    fn fat_context<C: ErrorContext<E>>(
+        self,
+        context: C,
+    ) -> impl Stream<Item = Result<T, C::Error>> {
+        self.map_err(move |error| context.attach(error))
+    }
+}
+
+impl<S: Stream<Item = Result<T, E>>, T, E> FatStreamExt<T, E> for S {}
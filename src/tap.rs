@@ -0,0 +1,55 @@
+use std::error::Error;
+
+/// # A level an error chain can be logged at by [`TapErrLog::tap_err_log`]
+/// Implemented for `log::Level` with the `log` feature, which logs with [`crate::log_error_chain!`] and the target `vg_errortools`,
+/// and for `tracing::Level` with the `tracing` feature, which records with [`crate::trace_error_chain`].
+pub trait ChainLevel {
+    /// log `error` and its causes at this level
+    fn log_chain(self, error: &(dyn Error + 'static));
+}
+
+#[cfg(feature = "log")]
+impl ChainLevel for log::Level {
+    fn log_chain(self, error: &(dyn Error + 'static)) {
+        crate::log_error_chain("vg_errortools", self, error);
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl ChainLevel for tracing::Level {
+    fn log_chain(self, error: &(dyn Error + 'static)) {
+        crate::trace_error_chain(self, error);
+    }
+}
+
+/// # Log the error of a `Result` with its chain and pass the `Result` on unchanged
+/// Observes errors at intermediate layers without restructuring the code.
+/// # Examples
+/// ```rust
+/// use std::error::Error;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use vg_errortools::{fat_io_wrap_std, ChainLevel, TapErrLog};
+/// static LOGGED: AtomicBool = AtomicBool::new(false);
+/// struct Audit;
+/// impl ChainLevel for Audit {
+///     fn log_chain(self, error: &(dyn Error + 'static)) {
+///         assert!(error.to_string().contains("does/not/exist.txt"));
+///         LOGGED.store(true, Ordering::Relaxed);
+///     }
+/// }
+/// let result = fat_io_wrap_std("does/not/exist.txt", &std::fs::read).tap_err_log(Audit);
+/// assert!(result.is_err() && LOGGED.load(Ordering::Relaxed));
+/// ```
+pub trait TapErrLog {
+    /// log the error, if any, at `level`
+    fn tap_err_log(self, level: impl ChainLevel) -> Self;
+}
+
+impl<T, E: Error + 'static> TapErrLog for Result<T, E> {
+    fn tap_err_log(self, level: impl ChainLevel) -> Self {
+        if let Err(error) = &self {
+            level.log_chain(error);
+        }
+        self
+    }
+}
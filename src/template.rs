@@ -0,0 +1,124 @@
+use crate::FatIOError;
+use std::borrow::Cow;
+use std::fmt;
+use std::fmt::{Formatter, Write};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// # Customize how a [`FatIOError`] phrases its message
+/// Install an implementation with [`set_message_template`] to change ordering, wording or included fields of the `Display` output,
+/// e.g. to follow a style guide for user-facing errors. The default methods produce the built-in text, translated by the `i18n` feature if a catalog is selected.
+/// Implementations must not display the `FatIOError` they are formatting, that would recurse.
+/// # Examples
+/// ```rust
+/// use std::fmt;
+/// use vg_errortools::{set_message_template, FatIOError, MessageTemplate};
+/// struct StyleGuide;
+/// impl MessageTemplate for StyleGuide {
+///     fn fat_io_error(&self, error: &FatIOError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "Could not access {}: {}.", error.path().display(), error.io_error())
+///     }
+/// }
+/// set_message_template(Some(Box::new(StyleGuide)));
+/// let fat = FatIOError::from_std_io_err(std::io::Error::other("disk on fire"), "scan.raw".into());
+/// assert_eq!(fat.to_string(), "Could not access scan.raw: disk on fire.");
+/// set_message_template(None);
+/// assert_eq!(fat.to_string(), "Operating on file 'scan.raw' failed with error disk on fire");
+/// ```
+///
+/// The built-in messages quote paths so they can be pasted into a shell, quotes and control characters are escaped:
+/// ```rust
+/// use vg_errortools::FatIOError;
+/// let fat = FatIOError::from_std_io_err(std::io::Error::other("disk on fire"), "it's\nraw ".into());
+/// assert_eq!(fat.to_string(), r"Operating on file 'it'\''s'$'\n''raw ' failed with error disk on fire");
+/// ```
+pub trait MessageTemplate: Send + Sync {
+    /// write the message of `error`
+    fn fat_io_error(&self, error: &FatIOError, f: &mut Formatter<'_>) -> fmt::Result {
+        default_fat_io_error(error, f)
+    }
+}
+
+fn default_fat_io_error(error: &FatIOError, f: &mut Formatter<'_>) -> fmt::Result {
+    let path = display_path(error.path());
+    if let Some(operation) = error.operation() {
+        let operation = operation.rsplit("::").next().unwrap_or(operation);
+        #[cfg(feature = "i18n")]
+        if let Some(text) = crate::i18n::phrase(
+            "fat-io-operation-error",
+            &[
+                ("operation", &operation),
+                ("path", &path),
+                ("error", error.io_error()),
+            ],
+        ) {
+            return f.write_str(&text);
+        }
+        return write!(
+            f,
+            "{operation} on '{path}' failed with error {}",
+            error.io_error()
+        );
+    }
+    #[cfg(feature = "i18n")]
+    if let Some(text) = crate::i18n::phrase(
+        "fat-io-error",
+        &[("path", &path), ("error", error.io_error())],
+    ) {
+        return f.write_str(&text);
+    }
+    write!(
+        f,
+        "Operating on file '{path}' failed with error {}",
+        error.io_error()
+    )
+}
+
+/// # The text of `path` inside the single quotes of the built-in messages
+/// Quotes and control characters are escaped the way POSIX shells concatenate quoted strings, e.g. `it'\''s` and `a'$'\n''b`,
+/// so the quoted path stays unambiguous and can be pasted into a shell.
+pub(crate) fn display_path(path: &Path) -> Cow<'_, str> {
+    let text = path.to_string_lossy();
+    #[cfg(feature = "unicode")]
+    let text = crate::normalize::normalize(text);
+    quote_text(text)
+}
+
+/// escape `text` like [`display_path`] does, for contexts other than paths
+pub(crate) fn quote_text(text: Cow<'_, str>) -> Cow<'_, str> {
+    if !text.chars().any(|c| c == '\'' || c.is_control()) {
+        return text;
+    }
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '\'' => escaped.push_str("'\\''"),
+            '\n' => escaped.push_str("'$'\\n''"),
+            '\r' => escaped.push_str("'$'\\r''"),
+            '\t' => escaped.push_str("'$'\\t''"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "'$'\\u{:04x}''", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.into()
+}
+
+/// shared, so messages are written without holding the lock and templates may format nested errors
+static TEMPLATE: RwLock<Option<Arc<dyn MessageTemplate>>> = RwLock::new(None);
+
+/// # Select the process wide [`MessageTemplate`], `None` restores the built-in messages
+/// Call this once at startup.
+pub fn set_message_template(template: Option<Box<dyn MessageTemplate>>) {
+    *TEMPLATE.write().unwrap_or_else(|e| e.into_inner()) = template.map(Arc::from);
+}
+
+/// write the message of `error` with the selected template
+pub(crate) fn fat_io_error(error: &FatIOError, f: &mut Formatter<'_>) -> fmt::Result {
+    let template = TEMPLATE.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match template.as_deref() {
+        Some(template) => template.fat_io_error(error, f),
+        None => default_fat_io_error(error, f),
+    }
+}
@@ -0,0 +1,462 @@
+use crate::template::display_path;
+use crate::{chain, fat_io_wrap_std, FatIOError};
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// # Equal if kind and path are equal
+/// OS message texts and other attached information are ignored, so tests can `assert_eq!` against an expected error on every platform.
+/// # Examples
+/// ```rust
+/// use std::io::ErrorKind;
+/// use vg_errortools::{fat_io_wrap_std, FatIOError};
+/// let error = fat_io_wrap_std("does/not/exist.txt", &std::fs::read).unwrap_err();
+/// assert_eq!(error, FatIOError::from_std_io_err(ErrorKind::NotFound.into(), "does/not/exist.txt".into()));
+/// ```
+impl PartialEq for FatIOError {
+    fn eq(&self, other: &Self) -> bool {
+        self.classify() == other.classify()
+    }
+}
+
+impl Eq for FatIOError {}
+
+/// the path with `/` separators and the temp directory replaced by `<tmp>`
+fn snapshot_path(path: &Path) -> String {
+    let (prefix, rest) = match path.strip_prefix(std::env::temp_dir()) {
+        Ok(rest) => (Some("<tmp>".to_string()), rest),
+        Err(_) => (None, path),
+    };
+    let components = rest
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().replace('\\', "/"));
+    let joined: Vec<String> = prefix.into_iter().chain(components).collect();
+    joined.join("/").replace("//", "/")
+}
+
+/// # Render an error chain deterministically for snapshot tests
+/// The output has one line for the error and one `caused by:` line per cause, without colors or report details.
+/// Paths of [`FatIOError`]s are written with `/` separators and the temp directory as `<tmp>`,
+/// the OS specific text of io errors like `No such file or directory (os error 2)` is replaced by the description of their kind,
+/// so snapshots match on Windows and Linux.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{fat_io_wrap_std, render_for_snapshot};
+/// let path = std::env::temp_dir().join("missing").join("scan.raw");
+/// let error = fat_io_wrap_std(&path, &std::fs::File::open).unwrap_err();
+/// assert_eq!(
+///     render_for_snapshot(&error),
+///     "Operating on file '<tmp>/missing/scan.raw' failed with error entity not found\ncaused by: entity not found"
+/// );
+/// ```
+pub fn render_for_snapshot(error: &(dyn Error + 'static)) -> String {
+    let mut replacements: Vec<(String, String)> = Vec::new();
+    for e in chain(error) {
+        if let Some(fat) = e.downcast_ref::<FatIOError>() {
+            let path = fat.path();
+            replacements.push((path.to_string_lossy().into_owned(), snapshot_path(path)));
+        }
+        let io = e
+            .downcast_ref::<std::io::Error>()
+            .or_else(|| e.downcast_ref::<FatIOError>().map(FatIOError::io_error));
+        if let Some(io) = io.filter(|io| io.raw_os_error().is_some()) {
+            replacements.push((io.to_string(), io.kind().to_string()));
+        }
+    }
+    let lines: Vec<String> = chain(error)
+        .map(|e| {
+            replacements.iter().fold(e.to_string(), |line, (from, to)| {
+                line.replace(from.as_str(), to)
+            })
+        })
+        .collect();
+    lines.join("\ncaused by: ")
+}
+
+/// a single check of an [`ErrorMatcher`]
+#[derive(Clone, Debug)]
+enum Check {
+    Contains(String),
+    Type(fn(&(dyn Error + 'static)) -> bool, &'static str),
+    Kind(ErrorKind),
+    PathEndsWith(PathBuf),
+}
+
+impl Check {
+    fn matches(&self, error: &(dyn Error + 'static)) -> bool {
+        match self {
+            Check::Contains(substring) => error.to_string().contains(substring.as_str()),
+            Check::Type(is, _) => is(error),
+            Check::Kind(kind) => {
+                let actual = error
+                    .downcast_ref::<FatIOError>()
+                    .map(FatIOError::kind)
+                    .or_else(|| {
+                        error
+                            .downcast_ref::<std::io::Error>()
+                            .map(std::io::Error::kind)
+                    });
+                actual == Some(*kind)
+            }
+            Check::PathEndsWith(end) => error
+                .downcast_ref::<FatIOError>()
+                .is_some_and(|fat| fat.path().ends_with(end)),
+        }
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::Contains(substring) => write!(f, "contains {substring:?}"),
+            Check::Type(_, name) => write!(f, "is {name}"),
+            Check::Kind(kind) => write!(f, "kind {kind:?}"),
+            Check::PathEndsWith(end) => write!(f, "path ends with '{}'", display_path(end)),
+        }
+    }
+}
+
+/// # The checks a single error of a chain must pass, see [`ErrorChainMatcher`]
+#[derive(Clone, Debug, Default)]
+pub struct ErrorMatcher {
+    checks: Vec<Check>,
+}
+
+impl ErrorMatcher {
+    /// a matcher accepting any error
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the message contains `substring`
+    pub fn contains(mut self, substring: impl Into<String>) -> Self {
+        self.checks.push(Check::Contains(substring.into()));
+        self
+    }
+
+    /// the error is a `T`
+    pub fn is<T: Error + 'static>(mut self) -> Self {
+        self.checks
+            .push(Check::Type(|e| e.is::<T>(), std::any::type_name::<T>()));
+        self
+    }
+
+    /// the error is an io error or a [`FatIOError`] of `kind`
+    pub fn kind(mut self, kind: ErrorKind) -> Self {
+        self.checks.push(Check::Kind(kind));
+        self
+    }
+
+    /// the error is a [`FatIOError`] whose path ends with `end`, compared by components
+    pub fn path_ends_with(mut self, end: impl Into<PathBuf>) -> Self {
+        self.checks.push(Check::PathEndsWith(end.into()));
+        self
+    }
+
+    fn matches(&self, error: &(dyn Error + 'static)) -> bool {
+        self.checks.iter().all(|check| check.matches(error))
+    }
+}
+
+impl Display for ErrorMatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.checks.is_empty() {
+            return f.write_str("any error");
+        }
+        for (i, check) in self.checks.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{check}")?;
+        }
+        Ok(())
+    }
+}
+
+/// # A programmatic matcher for error chains
+/// Each [`ErrorMatcher`] added with [`ErrorChainMatcher::then`] must match an error of the chain, in order, later matchers further down the chain.
+/// Errors in between are skipped, [`ErrorChainMatcher::exact`] requires each matcher to match the next error and no errors to be left over.
+/// On failure the message lists the whole chain with the matched errors marked.
+/// # Examples
+/// ```rust
+/// use std::io::ErrorKind;
+/// use vg_errortools::{ErrorChainMatcher, ErrorMatcher, FatIOError};
+/// let error = FatIOError::from_std_io_err(ErrorKind::NotFound.into(), "config/settings.json".into());
+/// let matcher = ErrorChainMatcher::new()
+///     .then(ErrorMatcher::new().is::<FatIOError>().path_ends_with("settings.json"))
+///     .then(ErrorMatcher::new().kind(ErrorKind::NotFound));
+/// matcher.assert(&error);
+/// let failure = ErrorChainMatcher::new()
+///     .then(ErrorMatcher::new().kind(ErrorKind::PermissionDenied))
+///     .check(&error)
+///     .unwrap_err();
+/// assert_eq!(
+///     failure,
+///     "no error matches #1: kind PermissionDenied\n\
+///      chain:\n\
+///      \x20 0   Operating on file 'config/settings.json' failed with error entity not found\n\
+///      \x20 1   entity not found"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ErrorChainMatcher {
+    matchers: Vec<ErrorMatcher>,
+    exact: bool,
+}
+
+impl ErrorChainMatcher {
+    /// a matcher accepting any chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// require an error further down the chain to match `matcher`
+    pub fn then(mut self, matcher: ErrorMatcher) -> Self {
+        self.matchers.push(matcher);
+        self
+    }
+
+    /// require the matchers to match the whole chain one by one, without skipping errors
+    pub fn exact(mut self) -> Self {
+        self.exact = true;
+        self
+    }
+
+    /// check `error`, describing the mismatch and the actual chain on failure
+    pub fn check(&self, error: &(dyn Error + 'static)) -> Result<(), String> {
+        let errors: Vec<&(dyn Error + 'static)> = chain(error).collect();
+        let mut matched = Vec::new();
+        let mut position = 0;
+        let mut failure = None;
+        for (i, matcher) in self.matchers.iter().enumerate() {
+            let found = if self.exact {
+                errors
+                    .get(position)
+                    .filter(|e| matcher.matches(**e))
+                    .map(|_| position)
+            } else {
+                (position..errors.len()).find(|&p| matcher.matches(errors[p]))
+            };
+            match found {
+                Some(p) => {
+                    matched.push(p);
+                    position = p + 1;
+                }
+                None => {
+                    failure = Some(format!("no error matches #{}: {matcher}", i + 1));
+                    break;
+                }
+            }
+        }
+        if failure.is_none() && self.exact && position < errors.len() {
+            failure = Some(format!(
+                "{} more errors than matchers",
+                errors.len() - position
+            ));
+        }
+        let Some(mut message) = failure else {
+            return Ok(());
+        };
+        message.push_str("\nchain:");
+        for (p, e) in errors.iter().enumerate() {
+            let mark = if matched.contains(&p) { '✓' } else { ' ' };
+            message.push_str(&format!("\n  {p} {mark} {e}"));
+        }
+        Err(message)
+    }
+
+    /// panic with the message of [`ErrorChainMatcher::check`] if `error` does not match
+    #[track_caller]
+    pub fn assert(&self, error: &(dyn Error + 'static)) {
+        if let Err(message) = self.check(error) {
+            panic!("{message}");
+        }
+    }
+}
+
+/// `*` matches any characters but `/`, `?` matches one character but `/`
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.split_first(), text.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            glob_matches(rest, text)
+                || text
+                    .split_first()
+                    .is_some_and(|(&c, tail)| c != b'/' && glob_matches(pattern, tail))
+        }
+        (Some((b'?', rest)), Some((&c, tail))) => c != b'/' && glob_matches(rest, tail),
+        (Some((p, rest)), Some((c, tail))) => p == c && glob_matches(rest, tail),
+        _ => false,
+    }
+}
+
+#[derive(Debug)]
+enum Fault {
+    Nth(usize, ErrorKind),
+    Glob(String, ErrorKind),
+}
+
+/// # A directory injecting io failures for testing error reporting
+/// Operations run through [`FaultyDir::wrap`] behave like [`crate::fat_io_wrap_std`], unless a configured fault applies:
+/// [`FaultyDir::fail_nth`] fails the n-th operation, [`FaultyDir::fail_matching`] fails all operations on paths matching a glob relative to the directory.
+/// An injected failure is a [`FatIOError`] with the configured kind, so applications can test their reporting of e.g. `PermissionDenied` without root or broken disks.
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+/// use std::io::ErrorKind;
+/// use vg_errortools::FaultyDir;
+/// let dir = FaultyDir::new(std::env::temp_dir())
+///     .fail_nth(2, ErrorKind::Interrupted)
+///     .fail_matching("secrets/*.key", ErrorKind::PermissionDenied);
+/// let scan = dir.path("vg_errortools_faulty_dir_doc.raw");
+/// std::fs::write(&scan, b"voxels").unwrap();
+/// assert!(dir.wrap(&scan, &File::open).is_ok());
+/// let second = dir.wrap(&scan, &File::open).unwrap_err();
+/// assert_eq!(second.kind(), ErrorKind::Interrupted);
+/// assert!(dir.wrap(&scan, &File::open).is_ok());
+/// let key = dir.wrap(&dir.path("secrets/server.key"), &File::open).unwrap_err();
+/// assert_eq!(key.kind(), ErrorKind::PermissionDenied);
+/// assert_eq!(dir.operations(), 4);
+/// ```
+#[derive(Debug)]
+pub struct FaultyDir {
+    root: PathBuf,
+    faults: Vec<Fault>,
+    operations: AtomicUsize,
+}
+
+impl FaultyDir {
+    /// wrap `root` without any faults
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FaultyDir {
+            root: root.into(),
+            faults: Vec::new(),
+            operations: AtomicUsize::new(0),
+        }
+    }
+
+    /// fail the `n`-th operation, counting from 1, with `kind`
+    pub fn fail_nth(mut self, n: usize, kind: ErrorKind) -> Self {
+        self.faults.push(Fault::Nth(n, kind));
+        self
+    }
+
+    /// fail every operation on a path matching `pattern` with `kind`, `*` and `?` match within one path component, `/` separates components
+    pub fn fail_matching(mut self, pattern: impl Into<String>, kind: ErrorKind) -> Self {
+        self.faults.push(Fault::Glob(pattern.into(), kind));
+        self
+    }
+
+    /// the path of `relative` in the directory
+    pub fn path(&self, relative: impl AsRef<Path>) -> PathBuf {
+        self.root.join(relative)
+    }
+
+    /// the number of operations run so far
+    pub fn operations(&self) -> usize {
+        self.operations.load(Ordering::Relaxed)
+    }
+
+    /// the kind of the injected failure for the operation with `number` on `path`, if any
+    fn fault(&self, number: usize, path: &Path) -> Option<ErrorKind> {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        self.faults.iter().find_map(|fault| match fault {
+            Fault::Nth(n, kind) => (*n == number).then_some(*kind),
+            Fault::Glob(pattern, kind) => {
+                glob_matches(pattern.as_bytes(), relative.as_bytes()).then_some(*kind)
+            }
+        })
+    }
+
+    /// run `f` like [`crate::fat_io_wrap_std`] or fail with an injected error
+    pub fn wrap<'a, T, P: AsRef<Path> + ?Sized>(
+        &self,
+        path: &'a P,
+        f: &dyn Fn(&'a P) -> std::io::Result<T>,
+    ) -> Result<T, FatIOError> {
+        let number = self.operations.fetch_add(1, Ordering::Relaxed) + 1;
+        match self.fault(number, path.as_ref()) {
+            Some(kind) => Err(FatIOError::from_std_io_err(
+                std::io::Error::new(kind, "injected failure"),
+                path.as_ref().to_path_buf(),
+            )),
+            None => fat_io_wrap_std(path, f),
+        }
+    }
+}
+
+/// # Assert that an error chain matches a sequence of substrings and types
+/// Each entry is either a string literal, which the message at that position must contain, or a type, which the error at that position must be.
+/// The chain must have exactly as many errors as entries. On mismatch the panic message shows the position and the whole chain.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{assert_error_chain, FatIOError};
+/// #[derive(Debug, thiserror::Error)]
+/// #[error("loading settings failed")]
+/// struct SettingsError(#[source] FatIOError);
+/// let fat = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "settings.json".into());
+/// let error = SettingsError(fat);
+/// assert_error_chain!(error, ["loading settings", FatIOError, "not found"]);
+/// assert_error_chain!(error, [SettingsError, "settings.json", std::io::Error]);
+/// ```
+///
+/// ```rust, should_panic
+/// use vg_errortools::assert_error_chain;
+/// let error = std::io::Error::other("disk on fire");
+/// assert_error_chain!(error, ["disk on fire", "caused by something"]);
+/// ```
+#[macro_export]
+macro_rules! assert_error_chain {
+    ($error:expr, [$($expected:tt)*] $(,)?) => {{
+        let error: &(dyn std::error::Error + 'static) = &$error;
+        let mut chain = std::iter::successors(Some(error), |e| e.source());
+        $crate::assert_error_chain!(@next error, chain, 0usize, $($expected)*);
+        if let Some(extra) = chain.next() {
+            panic!(
+                "error chain has more errors than expected, starting with '{extra}'\nchain: {}",
+                $crate::display_chain(error)
+            );
+        }
+    }};
+    (@next $error:ident, $chain:ident, $position:expr, $(,)?) => {};
+    (@next $error:ident, $chain:ident, $position:expr, $substring:literal $(, $($rest:tt)*)?) => {
+        match $chain.next() {
+            Some(actual) if actual.to_string().contains($substring) => {}
+            Some(actual) => panic!(
+                "error chain mismatch at position {}: expected a message containing {:?}, found '{actual}'\nchain: {}",
+                $position,
+                $substring,
+                $crate::display_chain($error)
+            ),
+            None => panic!(
+                "error chain ends before position {}, expected a message containing {:?}\nchain: {}",
+                $position,
+                $substring,
+                $crate::display_chain($error)
+            ),
+        }
+        $crate::assert_error_chain!(@next $error, $chain, $position + 1, $($($rest)*)?);
+    };
+    (@next $error:ident, $chain:ident, $position:expr, $expected:ty $(, $($rest:tt)*)?) => {
+        match $chain.next() {
+            Some(actual) if actual.is::<$expected>() => {}
+            Some(actual) => panic!(
+                "error chain mismatch at position {}: expected a {}, found '{actual}'\nchain: {}",
+                $position,
+                stringify!($expected),
+                $crate::display_chain($error)
+            ),
+            None => panic!(
+                "error chain ends before position {}, expected a {}\nchain: {}",
+                $position,
+                stringify!($expected),
+                $crate::display_chain($error)
+            ),
+        }
+        $crate::assert_error_chain!(@next $error, $chain, $position + 1, $($($rest)*)?);
+    };
+}
@@ -0,0 +1,112 @@
+use crate::panic::catch_panic;
+use crate::PanicError;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::panic::AssertUnwindSafe;
+use std::thread::{JoinHandle, Thread};
+
+#[derive(Debug)]
+enum ThreadFailure {
+    Spawn(std::io::Error),
+    Panic(PanicError),
+}
+
+/// # A thread which could not be spawned or panicked, with its name
+/// Created by [`fat_spawn_thread`] and [`FatJoinHandle::join`], the source is the io error of the spawn or the [`PanicError`].
+#[derive(Debug)]
+pub struct FatThreadError {
+    name: String,
+    failure: ThreadFailure,
+}
+
+impl FatThreadError {
+    /// the name of the thread
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// the panic, if the thread panicked
+    pub fn panic(&self) -> Option<&PanicError> {
+        match &self.failure {
+            ThreadFailure::Panic(panic) => Some(panic),
+            ThreadFailure::Spawn(_) => None,
+        }
+    }
+}
+
+impl Display for FatThreadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.failure {
+            ThreadFailure::Spawn(e) => write!(f, "spawning thread '{}' failed: {e}", self.name),
+            ThreadFailure::Panic(e) => write!(f, "thread '{}' failed: {e}", self.name),
+        }
+    }
+}
+
+impl Error for FatThreadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.failure {
+            ThreadFailure::Spawn(e) => Some(e),
+            ThreadFailure::Panic(e) => Some(e),
+        }
+    }
+}
+
+/// # A handle to a thread spawned by [`fat_spawn_thread`]
+#[derive(Debug)]
+pub struct FatJoinHandle<T> {
+    name: String,
+    handle: JoinHandle<Result<T, PanicError>>,
+}
+
+impl<T> FatJoinHandle<T> {
+    /// the spawned thread
+    pub fn thread(&self) -> &Thread {
+        self.handle.thread()
+    }
+
+    /// wait for the thread, a panic is returned as [`FatThreadError`] with the panic message and location
+    pub fn join(self) -> Result<T, FatThreadError> {
+        let failed = |panic| FatThreadError {
+            name: self.name.clone(),
+            failure: ThreadFailure::Panic(panic),
+        };
+        match self.handle.join() {
+            Ok(result) => result.map_err(failed),
+            // the closure catches its panics, so this only happens if the panic payload panicked on drop
+            Err(_) => Err(failed(PanicError::unknown())),
+        }
+    }
+}
+
+/// # Spawn a named thread with errors carrying the thread name
+/// Wraps `std::thread::Builder::spawn`, a failing spawn and a panic of the thread both become a [`FatThreadError`] naming the thread.
+/// # Examples
+/// ```rust
+/// use vg_errortools::fat_spawn_thread;
+/// let handle = fat_spawn_thread("converter-2", || -> u32 { panic!("corrupt batch") }).unwrap();
+/// let error = handle.join().unwrap_err();
+/// assert_eq!(error.panic().unwrap().message(), "corrupt batch");
+/// assert!(error.to_string().starts_with("thread 'converter-2' failed: panicked at "));
+/// ```
+pub fn fat_spawn_thread<F, T>(
+    name: impl Into<String>,
+    f: F,
+) -> Result<FatJoinHandle<T>, FatThreadError>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let name = name.into();
+    let spawned = std::thread::Builder::new()
+        .name(name.clone())
+        .spawn(move || catch_panic(AssertUnwindSafe(f)).map_err(|(panic, _)| panic));
+    match spawned {
+        Ok(handle) => Ok(FatJoinHandle { name, handle }),
+        Err(e) => Err(FatThreadError {
+            name,
+            failure: ThreadFailure::Spawn(e),
+        }),
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{chain, FatIOError};
+use std::error::Error;
+use std::path::Path;
+
+/// # Record an error chain as a tracing event with structured fields
+/// The event carries `error.message`, `error.causes` (the messages of all causes in order) and, if an io error or [`FatIOError`] is part of the chain, `error.kind` and `error.path`.
+/// A correlation ID of a [`FatIOError`] in the chain is recorded as `error.correlation_id`.
+/// # Examples
+/// ```rust
+/// use vg_errortools::{trace_error_chain, FatIOError};
+/// let error = FatIOError::from_std_io_err(std::io::ErrorKind::NotFound.into(), "my_file.txt".into());
+/// trace_error_chain(tracing::Level::WARN, &error);
+/// ```
+pub fn trace_error_chain(level: tracing::Level, error: &(dyn Error + 'static)) {
+    let causes: Vec<String> = chain(error).skip(1).map(|e| e.to_string()).collect();
+    let fat = chain(error).find_map(|e| e.downcast_ref::<FatIOError>());
+    let kind = fat.map(FatIOError::kind).or_else(|| {
+        chain(error).find_map(|e| e.downcast_ref::<std::io::Error>().map(std::io::Error::kind))
+    });
+    let kind = kind.map(tracing::field::debug);
+    let path = fat.map(|fat| tracing::field::display(fat.path().display()));
+    let correlation_id = chain(error)
+        .filter_map(|e| e.downcast_ref::<FatIOError>())
+        .find_map(FatIOError::correlation_id);
+    macro_rules! event {
+        ($level:expr) => {
+            tracing::event!(
+                $level,
+                error.message = %error,
+                error.kind = kind,
+                error.path = path,
+                error.causes = ?causes,
+                error.correlation_id = correlation_id,
+            )
+        };
+    }
+    match level {
+        tracing::Level::ERROR => event!(tracing::Level::ERROR),
+        tracing::Level::WARN => event!(tracing::Level::WARN),
+        tracing::Level::INFO => event!(tracing::Level::INFO),
+        tracing::Level::DEBUG => event!(tracing::Level::DEBUG),
+        tracing::Level::TRACE => event!(tracing::Level::TRACE),
+    }
+}
+
+/// # Wrapper for std::io functions running inside a tracing span
+/// Same as [`crate::fat_io_wrap_std`], but the function runs inside a `fat_io` span carrying the `path`, and a failure is recorded with [`trace_error_chain`] at error level.
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+/// use vg_errortools::fat_io_wrap_std_traced;
+/// assert!(fat_io_wrap_std_traced("does/not/exist.txt", &File::open).is_err());
+/// ```
+pub fn fat_io_wrap_std_traced<'a, T, P: AsRef<Path> + ?Sized>(
+    path: &'a P,
+    f: &dyn Fn(&'a P) -> std::io::Result<T>,
+) -> Result<T, FatIOError> {
+    let span = tracing::debug_span!("fat_io", path = %path.as_ref().display());
+    let _entered = span.enter();
+    crate::fat_io_wrap_std(path, f).inspect_err(|e| trace_error_chain(tracing::Level::ERROR, e))
+}
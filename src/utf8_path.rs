@@ -0,0 +1,27 @@
+use crate::FatIOError;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::sync::Arc;
+
+impl FatIOError {
+    /// create a FatIOError from a `camino` path, the path displays losslessly
+    /// # Examples
+    /// ```rust
+    /// use camino::Utf8PathBuf;
+    /// use vg_errortools::{fat_io_wrap_std, FatIOError};
+    /// let path = Utf8PathBuf::from("volumes/ct_ü.raw");
+    /// let fat = fat_io_wrap_std(&path, &std::fs::File::open).unwrap_err();
+    /// assert_eq!(fat.utf8_path(), Some(path.as_path()));
+    /// let fat = FatIOError::from_utf8_path(std::io::ErrorKind::NotFound.into(), path.clone());
+    /// assert_eq!(fat.utf8_path(), Some(path.as_path()));
+    /// ```
+    pub fn from_utf8_path(e: std::io::Error, file: Utf8PathBuf) -> Self {
+        FatIOError::from_shared_path(e, Arc::from(file.into_std_path_buf()))
+    }
+
+    /// the path of the file the operation failed on as `camino` path, `None` if it is not valid UTF-8
+    ///
+    /// Paths stored from a `Utf8Path`, also through the wrappers, are always valid UTF-8.
+    pub fn utf8_path(&self) -> Option<&Utf8Path> {
+        Utf8Path::from_path(self.path())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{canonical, fat_io_wrap_std, FatIOError};
+use digest::Digest;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// # A checksum which did not match the expected one
+/// The io error of kind `InvalidData` returned by [`fat_read_verified`] wraps it, inside a [`FatIOError`] for the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumMismatch {
+    expected: Vec<u8>,
+    actual: Vec<u8>,
+}
+
+impl ChecksumMismatch {
+    /// the expected hash
+    pub fn expected(&self) -> &[u8] {
+        &self.expected
+    }
+
+    /// the hash of the file content
+    pub fn actual(&self) -> &[u8] {
+        &self.actual
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+impl Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checksum mismatch, expected {} but found {}",
+            hex(&self.expected),
+            hex(&self.actual)
+        )
+    }
+}
+
+impl Error for ChecksumMismatch {}
+
+/// # Read a file and verify its hash
+/// The content is hashed with `D`, e.g. `sha2::Sha256`, and compared with the raw bytes of `expected`.
+/// On mismatch the error is a [`FatIOError`] of kind `InvalidData` with the path, wrapping a [`ChecksumMismatch`] with both hashes.
+/// # Examples
+/// ```rust
+/// use sha2::{Digest, Sha256};
+/// use vg_errortools::{fat_read_verified, ChecksumMismatch};
+/// let path = std::env::temp_dir().join("vg_errortools_verified_doc.bin");
+/// std::fs::write(&path, b"artifact").unwrap();
+/// let hash = Sha256::digest(b"artifact");
+/// assert_eq!(fat_read_verified::<Sha256>(&path, hash).unwrap(), b"artifact");
+/// let error = fat_read_verified::<Sha256>(&path, [0u8; 32]).unwrap_err();
+/// assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+/// let mismatch = error.io_error().get_ref().unwrap().downcast_ref::<ChecksumMismatch>().unwrap();
+/// assert_eq!(mismatch.actual(), hash.as_slice());
+/// assert!(error.to_string().contains("checksum mismatch, expected 0000"));
+/// ```
+pub fn fat_read_verified<D: Digest>(
+    path: impl AsRef<Path>,
+    expected: impl AsRef<[u8]>,
+) -> Result<Vec<u8>, FatIOError> {
+    let path = path.as_ref();
+    let content = fat_io_wrap_std(path, &std::fs::read)?;
+    let actual = D::digest(&content);
+    if actual.as_slice() == expected.as_ref() {
+        return Ok(content);
+    }
+    let mismatch = ChecksumMismatch {
+        expected: expected.as_ref().to_vec(),
+        actual: actual.to_vec(),
+    };
+    Err(FatIOError::from_shared_path(
+        std::io::Error::new(ErrorKind::InvalidData, mismatch),
+        canonical::recorded_path(path),
+    ))
+}